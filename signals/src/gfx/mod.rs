@@ -2,7 +2,10 @@ use std::borrow::Cow;
 
 use raylib::prelude::{RaylibDraw, RaylibDrawHandle};
 
-use crate::world::{self, Chunk, World};
+use crate::{
+	game::Worlds,
+	world::{self, Chunk, World},
+};
 use raylib::color::Color;
 
 pub const fn color(r: u8, g: u8, b: u8, a: u8) -> Color {
@@ -17,6 +20,20 @@ pub const NOT_BASE: Color = color(39, 143, 86, 255);
 pub const NOT_ON: Color = color(82, 81, 80, 255);
 pub const NOT_OFF: Color = color(255, 255, 255, 255);
 pub const REST_ON: Color = color(150, 150, 150, 255);
+pub const DELAY_BASE: Color = color(143, 86, 39, 255);
+pub const MEMORY_BASE: Color = color(39, 86, 143, 255);
+pub const CLOCK_BASE: Color = color(143, 39, 130, 255);
+pub const BUTTON_BASE: Color = color(60, 60, 60, 255);
+pub const BUTTON_TOP: Color = color(190, 50, 50, 255);
+pub const DISPLAY_BASE: Color = color(20, 20, 20, 255);
+pub const SEGMENT_ON: Color = color(255, 60, 40, 255);
+pub const SEGMENT_OFF: Color = color(50, 30, 30, 255);
+pub const WIRELESS_BASE: Color = color(39, 130, 143, 255);
+pub const BUS_ON: Color = color(240, 190, 60, 255);
+pub const BUS_OFF: Color = color(90, 70, 20, 255);
+pub const ANNOTATION: Color = color(255, 230, 120, 255);
+pub const PROBE: Color = color(80, 220, 120, 255);
+pub const LOOPING: Color = color(255, 60, 200, 90);
 
 // pub const WIRE_ON: Color = color(207, 109, 173, 255);
 // pub const WIRE_OFF: Color = color(105, 38, 81, 255);
@@ -31,48 +48,9 @@ pub const DEBUG_WIRES: bool = false;
 pub const DEBUG_CHUNKS: bool = false;
 pub const DEBUG_NOT: bool = false;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
-/// this is the enum that is used to determine what type of a block should be rendered at a position
-pub enum DrawType {
-	#[default]
-	Off,
-	On,
-	/// junction stores values for both axis, they should be reset before every render,
-	/// and entering false won't actually set it to false if it's already true from that same tick
-	Junction {
-		vertical: bool,
-		horizontal: bool,
-	},
-}
-impl From<bool> for DrawType {
-	fn from(value: bool) -> Self {
-		if value {
-			Self::On
-		} else {
-			Self::Off
-		}
-	}
-}
-impl DrawType {
-	pub fn apply_new(self, new: Self) -> Self {
-		match new {
-			DrawType::Junction {
-				vertical: new_vert,
-				horizontal: new_horizontal,
-			} => match self {
-				DrawType::Junction {
-					vertical: prev_vert,
-					horizontal: prev_horizontal,
-				} => DrawType::Junction {
-					vertical: prev_vert || new_vert,
-					horizontal: prev_horizontal || new_horizontal,
-				},
-				_ => new,
-			},
-			_ => new,
-		}
-	}
-}
+pub use signals_core::drawtype::DrawType;
+use signals_core::oscillation::Activity;
+
 pub type Drawmap = world::Chunk<DrawType>;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -131,6 +109,120 @@ pub fn render_world(
 		},
 		true,
 	);
+	render_annotations(world, d, pos_info);
+	render_probes(world, d, pos_info);
+}
+/// outlines every probe, with its name under it
+pub fn render_probes(world: &world::World, d: &mut RaylibDrawHandle, pos_info: PosInfo) {
+	for ((x, y), name) in world.probes() {
+		let pos_info = pos_info.transform(x * world::BLOCK_SIZE, y * world::BLOCK_SIZE);
+		d.draw_rectangle_lines(
+			pos_info.base.0,
+			pos_info.base.1,
+			pos_info.scale(world::BLOCK_SIZE),
+			pos_info.scale(world::BLOCK_SIZE),
+			PROBE,
+		);
+		d.draw_text(
+			name,
+			pos_info.base.0,
+			pos_info.base.1 + pos_info.scale(world::BLOCK_SIZE),
+			pos_info.scale(8),
+			PROBE,
+		);
+	}
+}
+/// tints every block that's part of a self-sustaining loop, see [Activity::Repeating]
+pub fn render_loops(activity: &Activity, d: &mut RaylibDrawHandle, pos_info: PosInfo) {
+	let looping = match activity {
+		Activity::Repeating { looping, .. } => looping,
+		_ => return,
+	};
+	for &(x, y) in looping {
+		let pos_info = pos_info.transform(x * world::BLOCK_SIZE, y * world::BLOCK_SIZE);
+		d.draw_rectangle(
+			pos_info.base.0,
+			pos_info.base.1,
+			pos_info.scale(world::BLOCK_SIZE),
+			pos_info.scale(world::BLOCK_SIZE),
+			LOOPING,
+		);
+	}
+}
+/// renders the annotation layer over the world's blocks
+pub fn render_annotations(world: &world::World, d: &mut RaylibDrawHandle, pos_info: PosInfo) {
+	for ((x, y), text) in world.annotations() {
+		let pos_info = pos_info.transform(x * world::BLOCK_SIZE, y * world::BLOCK_SIZE);
+		d.draw_text(
+			text,
+			pos_info.base.0,
+			pos_info.base.1 - pos_info.scale(10),
+			pos_info.scale(10),
+			ANNOTATION,
+		);
+	}
+}
+/// renders the name and color of every input, output and foreign in the world, see [world::Pins] \
+/// foreigns show both the input and the output of their id in the world they reference
+pub fn render_pins(
+	world: &world::World,
+	worlds: &Worlds,
+	d: &mut RaylibDrawHandle,
+	pos_info: PosInfo,
+) {
+	use world::PinKind;
+
+	for ((x, y), block) in world.blocks() {
+		let infos = match *block {
+			world::Block::Input(id) => vec![world.pins().get(PinKind::Input, id)],
+			world::Block::Output(id) => vec![world.pins().get(PinKind::Output, id)],
+			world::Block::Foreign(wid, _, id) => match worlds.at(wid) {
+				Some(foreign) => vec![
+					foreign.pins().get(PinKind::Input, id),
+					foreign.pins().get(PinKind::Output, id),
+				],
+				None => continue,
+			},
+			_ => continue,
+		};
+		if infos.iter().all(|info| info.is_none()) {
+			continue;
+		}
+
+		let pos_info = pos_info.transform(x * world::BLOCK_SIZE, y * world::BLOCK_SIZE);
+
+		// a strip of color along the bottom for each pin, input on the left for foreigns
+		let strip_width = world::BLOCK_SIZE / infos.len() as i32;
+		let strip_height = world::BLOCK_SIZE / 8;
+		for (i, info) in infos.iter().enumerate() {
+			if let Some((r, g, b)) = info.and_then(|info| info.color) {
+				let strip =
+					pos_info.transform(strip_width * i as i32, world::BLOCK_SIZE - strip_height);
+				d.draw_rectangle(
+					strip.base.0,
+					strip.base.1,
+					pos_info.scale(strip_width),
+					pos_info.scale(strip_height),
+					color(r, g, b, 255),
+				);
+			}
+		}
+
+		let names = infos
+			.iter()
+			.flatten()
+			.map(|info| info.name.as_str())
+			.filter(|name| !name.is_empty())
+			.collect::<Vec<_>>()
+			.join("/");
+		d.draw_text(
+			&names,
+			pos_info.base.0,
+			pos_info.base.1 + pos_info.scale(world::BLOCK_SIZE),
+			pos_info.scale(8),
+			SWITCH_ON,
+		);
+	}
 }
 fn render_any_world<'a>(
 	world: &world::World,
@@ -241,6 +333,71 @@ pub fn render_block(
 				);
 			}
 		}
+		world::Block::Bus(dir, width) => {
+			let horizontal = match dir {
+				world::Direction::Bottom | world::Direction::Top => false,
+				_ => true,
+			};
+			// a bit thicker than wires so they're easy to tell apart
+			let off = world::BLOCK_SIZE / 6;
+			let x_off = if !horizontal { off } else { 0 };
+			let y_off = if horizontal { off } else { 0 };
+
+			let color = if let DrawType::On = dt {
+				BUS_ON
+			} else {
+				BUS_OFF
+			};
+
+			let pos_info = pos_info.transform(x_off, y_off);
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE - x_off * 2),
+				pos_info.scale(world::BLOCK_SIZE - y_off * 2),
+				color,
+			);
+
+			if draw_misc {
+				d.draw_text(
+					&format!("{width}"),
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(8),
+					NOT_BASE,
+				);
+			}
+		}
+		world::Block::Splitter(dir, bit) | world::Block::Merger(dir, bit) => {
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				BUS_OFF,
+			);
+			render_block(
+				&world::Block::Bus(*dir, world::Block::BUS_MAX_WIDTH),
+				dt,
+				d,
+				pos_info,
+				false,
+			);
+
+			if draw_misc {
+				let label = match block {
+					world::Block::Splitter(..) => "<",
+					_ => ">",
+				};
+				d.draw_text(
+					&format!("{label}{bit}"),
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(10),
+					SWITCH_ON,
+				);
+			}
+		}
 		world::Block::Switch(state) => {
 			d.draw_rectangle(
 				pos_info.base.0,
@@ -298,6 +455,103 @@ pub fn render_block(
 				);
 			}
 		}
+		world::Block::Delay(dir, ticks, _) => {
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				DELAY_BASE,
+			);
+			render_block(&world::Block::Wire(*dir), dt, d, pos_info, draw_misc);
+
+			if draw_misc {
+				d.draw_text(
+					&format!("{ticks}"),
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(10),
+					SWITCH_ON,
+				);
+			}
+		}
+		world::Block::Latch(dir, ..) | world::Block::FlipFlop(dir, ..) => {
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				MEMORY_BASE,
+			);
+			render_block(&world::Block::Wire(*dir), dt, d, pos_info, draw_misc);
+
+			if draw_misc {
+				let label = match block {
+					world::Block::Latch(..) => "SR",
+					_ => "D",
+				};
+				d.draw_text(
+					label,
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(10),
+					SWITCH_ON,
+				);
+			}
+		}
+		world::Block::Clock { period, .. } => {
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				CLOCK_BASE,
+			);
+			if draw_misc {
+				d.draw_text(
+					&format!("clk\n{period}"),
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(10),
+					SWITCH_ON,
+				);
+			}
+		}
+		world::Block::Button { pulse, .. } => {
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				BUTTON_BASE,
+			);
+			let off = world::BLOCK_SIZE / 4;
+			let top = pos_info.transform(off, off);
+			d.draw_rectangle(
+				top.base.0,
+				top.base.1,
+				pos_info.scale(world::BLOCK_SIZE - off * 2),
+				pos_info.scale(world::BLOCK_SIZE - off * 2),
+				BUTTON_TOP,
+			);
+			if *pulse && draw_misc {
+				d.draw_text(
+					"p",
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(8),
+					SWITCH_ON,
+				);
+			}
+		}
+		world::Block::Display(mode, 0) => {
+			let pins = match dt {
+				DrawType::Display { pins } => *pins,
+				_ => 0,
+			};
+			render_display(*mode, mode.segments(pins), d, pos_info);
+		}
+		world::Block::Display(..) => {} // the whole display is rendered by pin 0
 		world::Block::Junction => {
 			let (vert_dt, horiz_dt) = match dt {
 				DrawType::Junction {
@@ -340,6 +594,33 @@ pub fn render_block(
 				)
 			}
 		}
+		world::Block::Transmitter(channel) | world::Block::Receiver(channel) => {
+			let color = if *dt == DrawType::On {
+				REST_ON
+			} else {
+				WIRELESS_BASE
+			};
+			d.draw_rectangle(
+				pos_info.base.0,
+				pos_info.base.1,
+				pos_info.scale(world::BLOCK_SIZE),
+				pos_info.scale(world::BLOCK_SIZE),
+				color,
+			);
+			if draw_misc {
+				let label = match block {
+					world::Block::Transmitter(_) => "tx",
+					_ => "rx",
+				};
+				d.draw_text(
+					&format!("{label}\n{channel}"),
+					pos_info.base.0 + pos_info.scale(2),
+					pos_info.base.1 + pos_info.scale(2),
+					pos_info.scale(10),
+					SWITCH_ON,
+				);
+			}
+		}
 		world::Block::Foreign(wid, inst_id, id) => {
 			let color = if *dt == DrawType::On {
 				REST_ON
@@ -388,3 +669,63 @@ pub fn render_block(
 		}
 	}
 }
+
+/// renders a whole display with its top left corner at pos_info \
+/// segments: bit 0 is segment a, bit 7 is the decimal point, see [world::DisplayMode::segments]
+fn render_display(
+	mode: world::DisplayMode,
+	segments: u8,
+	d: &mut RaylibDrawHandle,
+	pos_info: PosInfo,
+) {
+	let (cols, rows) = mode
+		.footprint()
+		.fold((0, 0), |(cols, rows), ((rx, ry), _)| {
+			(cols.max(rx + 1), rows.max(ry + 1))
+		});
+	let (w, h) = (cols * world::BLOCK_SIZE, rows * world::BLOCK_SIZE);
+	d.draw_rectangle(
+		pos_info.base.0,
+		pos_info.base.1,
+		pos_info.scale(w),
+		pos_info.scale(h),
+		DISPLAY_BASE,
+	);
+
+	let pad = world::BLOCK_SIZE / 4;
+	let t = w / 8; // segment thickness
+				// the digit itself, leaving space for the decimal point on the right
+	let (dx, dy, dw, dh) = (pad, pad, w - pad * 2 - t * 2, h - pad * 2);
+	let half = dh / 2 - t * 3 / 2; // height of a vertical segment
+
+	// (segment, x, y, width, height)
+	let segment_rects = [
+		(0, dx + t, dy, dw - t * 2, t),
+		(1, dx + dw - t, dy + t, t, half),
+		(2, dx + dw - t, dy + dh / 2 + t / 2, t, half),
+		(3, dx + t, dy + dh - t, dw - t * 2, t),
+		(4, dx, dy + dh / 2 + t / 2, t, half),
+		(5, dx, dy + t, t, half),
+		(6, dx + t, dy + dh / 2 - t / 2, dw - t * 2, t),
+		(7, dx + dw + t, dy + dh - t, t, t),
+	];
+	for (segment, x, y, seg_w, seg_h) in segment_rects {
+		if segment == 7 && mode == world::DisplayMode::Hex {
+			continue;
+		}
+		let color = if segments & 1 << segment != 0 {
+			SEGMENT_ON
+		} else {
+			SEGMENT_OFF
+		};
+
+		let pos_info = pos_info.transform(x, y);
+		d.draw_rectangle(
+			pos_info.base.0,
+			pos_info.base.1,
+			pos_info.scale(seg_w),
+			pos_info.scale(seg_h),
+			color,
+		);
+	}
+}
@@ -1,11 +1,10 @@
 mod assets;
-mod game;
 mod gfx;
 mod menu;
-mod processor;
 mod tool;
 mod ui;
-mod world;
+
+use signals_core::{game, processor, world};
 
 use std::{env, ops::DerefMut};
 
@@ -19,7 +18,7 @@ use raylib::{
 };
 use sui::{comp::fit::scrollable, core::Store, form::FocusHandler, Layable, LayableExt};
 use tool::Tool;
-use ui::{worlds_bar, SignalsEvent};
+use ui::{waveform, worlds_bar, SignalsEvent};
 
 pub const TICK_TIME: f32 = 0.03;
 pub const MOVE_UP: KeyboardKey = KeyboardKey::KEY_W;
@@ -27,6 +26,21 @@ pub const MOVE_DOWN: KeyboardKey = KeyboardKey::KEY_S;
 pub const MOVE_LEFT: KeyboardKey = KeyboardKey::KEY_A;
 pub const MOVE_RIGHT: KeyboardKey = KeyboardKey::KEY_D;
 pub const TOOL_USE: MouseButton = MouseButton::MOUSE_BUTTON_LEFT;
+/// writes what the probes recorded next to the save, see [signals_core::trace::Trace]
+pub const EXPORT_TRACE: KeyboardKey = KeyboardKey::KEY_V;
+/// with control held
+pub const UNDO: KeyboardKey = KeyboardKey::KEY_Z;
+/// with control held
+pub const REDO: KeyboardKey = KeyboardKey::KEY_Y;
+pub const PAUSE: KeyboardKey = KeyboardKey::KEY_P;
+/// pauses if it isn't already
+pub const STEP_BACK: KeyboardKey = KeyboardKey::KEY_LEFT;
+/// pauses if it isn't already
+pub const STEP_FORWARD: KeyboardKey = KeyboardKey::KEY_RIGHT;
+/// switches processors between taking one tick and taking as many ticks as simulating would
+pub const TICK_ACCURATE: KeyboardKey = KeyboardKey::KEY_T;
+/// how many ticks stepped through while paused can be stepped back through
+pub const REWIND_TICKS: usize = 300;
 pub const MOVE_AMOUNT: f32 = 5000.0;
 
 fn frame_dialog(comp: sui::Comp<'static>) -> sui::Comp<'static> {
@@ -69,11 +83,14 @@ async fn start<A: Assets>(
 			Default::default()
 		}
 	};
+	game.rewind = game::Rewind::new(REWIND_TICKS);
+	let mut paused = false;
 
 	let mut tool: tool::Tool = Default::default();
 	let tool_select = sui::SelectBar::new(tool::TOOLS);
 
 	let mut worlds_bar = worlds_bar::WorldsBar::default();
+	let mut waveform = waveform::WaveformPanel::default();
 
 	let mut dbg_cache = sui::core::Cached::default();
 	let dbg_scroll_state = Store::new(Default::default());
@@ -105,6 +122,34 @@ async fn start<A: Assets>(
 			inst_comp_counter += 1;
 			println!("triggering instruction recompute")
 		}
+		if rl.is_key_pressed(EXPORT_TRACE) {
+			match &game.trace {
+				Some(trace) => {
+					let path = format!("{save_path}.vcd");
+					match std::fs::write(&path, trace.to_vcd()) {
+						Ok(_) => println!("wrote probes to {path}"),
+						Err(err) => eprintln!("couldn't write probes to {path}\n{err}"),
+					}
+				}
+				None => eprintln!("place some probes to record a trace"),
+			}
+		}
+		if rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+			|| rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL)
+		{
+			let done = if rl.is_key_pressed(UNDO) {
+				Some(game.undo())
+			} else if rl.is_key_pressed(REDO) {
+				Some(game.redo())
+			} else {
+				None
+			};
+			match done {
+				Some(Ok(false)) => eprintln!("nothing to undo or redo"),
+				Some(Err(err)) => eprintln!("error while undoing or redoing:\n{err}"),
+				_ => {}
+			}
+		}
 
 		g_pos.scale *= 1.0 + (rl.get_mouse_wheel_move() * 0.1);
 
@@ -122,9 +167,39 @@ async fn start<A: Assets>(
 			g_pos.base.0 -= move_amount;
 		}
 
-		delta += rl.get_frame_time();
-		for _ in 0..(delta / TICK_TIME) as i32 {
-			delta -= TICK_TIME;
+		if rl.is_key_pressed(TICK_ACCURATE) {
+			let timing = match game.timing {
+				processor::Timing::Instant => processor::Timing::TickAccurate,
+				processor::Timing::TickAccurate => processor::Timing::Instant,
+			};
+			if let Err(err) = game.set_timing(timing) {
+				eprintln!("couldn't switch processor timing:\n{err}");
+			}
+		}
+		if rl.is_key_pressed(PAUSE) {
+			paused = !paused;
+		}
+		let mut ticks = 0;
+		if rl.is_key_pressed(STEP_BACK) {
+			paused = true;
+			if !game.step_back() {
+				eprintln!("can't step back any further");
+			}
+		}
+		if rl.is_key_pressed(STEP_FORWARD) {
+			paused = true;
+			ticks += 1;
+		}
+		game.rewind.set_paused(paused);
+
+		if paused {
+			delta = 0.0;
+		} else {
+			delta += rl.get_frame_time();
+			ticks += (delta / TICK_TIME) as i32;
+			delta -= ticks as f32 * TICK_TIME;
+		}
+		for _ in 0..ticks {
 			match game.tick() {
 				Ok(_) => (),
 				Err(err) => {
@@ -141,6 +216,14 @@ async fn start<A: Assets>(
 			(mouse_y as f32 - pos_info.base.1 as f32) / world::BLOCK_SIZE as f32 / pos_info.scale,
 		);
 
+		// next to the debug ui, on the other side of the screen
+		let waveform_det = sui::Details {
+			x: screen.aw - waveform::PANEL_WIDTH,
+			y: 100,
+			aw: waveform::PANEL_WIDTH,
+			ah: waveform::PANEL_HEIGHT,
+		};
+
 		let worlds_bar_h = 400 as f32 / 1980 as f32 * screen.ah as f32;
 		let worlds_bar_h = worlds_bar_h as i32;
 		// modified so width reflects the real width
@@ -168,6 +251,8 @@ async fn start<A: Assets>(
 				},
 				1.0,
 			);
+			let waveform_page = waveform.comp(&game);
+			let waveform_ctx = waveform_page.root_context(waveform_det, 1.0);
 			{
 				let mut d = sui::Handle::new(d, &focus_handler);
 
@@ -186,6 +271,7 @@ async fn start<A: Assets>(
 					worlds_bar_ctx
 						.handle_input(d.deref_mut(), &focus_handler)
 						.chain(dbg_ctx.handle_input(d.deref_mut(), &focus_handler))
+						.chain(waveform_ctx.handle_input(d.deref_mut(), &focus_handler))
 						.collect()
 				} else {
 					events
@@ -195,6 +281,8 @@ async fn start<A: Assets>(
 
 				if let Some(main) = game.main() {
 					gfx::render_world(&main, &mut d, pos_info, &game.drawmap);
+					gfx::render_pins(&main, &game.worlds, &mut d, pos_info);
+					gfx::render_loops(game.oscillation.activity(), &mut d, pos_info);
 				} else {
 					// temporary text to differentiate a non-world from an empty world
 					use sui::{comp, core::Layable};
@@ -212,11 +300,26 @@ async fn start<A: Assets>(
 
 				tool_select.render(&mut d, tool_select_det, Some(&tool));
 				dbg_ctx.render(&mut d);
+				waveform_ctx.render(&mut d);
 				worlds_bar_ctx.render(&mut d);
 
 				dialog_ctx.render(&mut d);
 
-				sui::text(format!("({point_x}, {point_y})"), 32).render(
+				let activity = game.oscillation.activity();
+				let pause_text = if paused {
+					format!(", paused at tick {}", game.ticks)
+				} else {
+					String::new()
+				};
+				let timing_text = match game.timing {
+					processor::Timing::Instant => "",
+					processor::Timing::TickAccurate => ", tick accurate",
+				};
+				sui::text(
+					format!("({point_x}, {point_y}) {activity}{pause_text}{timing_text}"),
+					32,
+				)
+				.render(
 					&mut d,
 					sui::Details {
 						x: 0,
@@ -232,17 +335,76 @@ async fn start<A: Assets>(
 
 		{
 			let tool_select_trig = tool_select.tick(rl, tool_select_det, &mut tool);
-			if !tool_select_trig && !worlds_bar_det.is_inside(mouse_x, mouse_y) {
+			if !tool_select_trig
+				&& !worlds_bar_det.is_inside(mouse_x, mouse_y)
+				&& !waveform_det.is_inside(mouse_x, mouse_y)
+			{
 				if rl.is_mouse_button_down(TOOL_USE) {
+					// everything from here until the button is released is one edit
+					game.start_edit();
 					tool.down(point_x, point_y, &mut game);
 				}
 				if rl.is_mouse_button_pressed(TOOL_USE) {
 					tool.pressed(point_x, point_y, &mut game);
+
+					match (&tool, game.main().and_then(|main| main.at(point_x, point_y))) {
+						(Tool::Interact, Some(&world::Block::Clock { period, .. })) => {
+							dialog_handler.run(ui::dialogs::clock_dialog(
+								(point_x, point_y),
+								period,
+								(mouse_x, mouse_y),
+							));
+						}
+						(Tool::Interact, Some(&world::Block::Input(id))) => {
+							dialog_handler.run(pin_dialog(
+								&game,
+								world::PinKind::Input,
+								id,
+								(mouse_x, mouse_y),
+							));
+						}
+						(Tool::Interact, Some(&world::Block::Output(id))) => {
+							dialog_handler.run(pin_dialog(
+								&game,
+								world::PinKind::Output,
+								id,
+								(mouse_x, mouse_y),
+							));
+						}
+						_ => {}
+					}
+					if let Tool::Annotate = tool {
+						let text = game
+							.main()
+							.and_then(|main| main.annotation(point_x, point_y))
+							.unwrap_or_default()
+							.to_string();
+						dialog_handler.run(ui::dialogs::annotation_dialog(
+							(point_x, point_y),
+							text,
+							(mouse_x, mouse_y),
+						));
+					}
+					if let Tool::Probe = tool {
+						let name = game
+							.main()
+							.and_then(|main| main.probe(point_x, point_y))
+							.map(|name| name.to_string())
+							.unwrap_or_else(|| format!("probe_{point_x}_{point_y}"));
+						dialog_handler.run(ui::dialogs::probe_dialog(
+							(point_x, point_y),
+							name,
+							(mouse_x, mouse_y),
+						));
+					}
 				}
 				if rl.is_mouse_button_released(TOOL_USE) {
 					tool.released(point_x, point_y, &mut game);
 				}
 			}
+			if rl.is_mouse_button_released(TOOL_USE) {
+				game.finish_edit();
+			}
 		}
 
 		let mut handle_event = |event: SignalsEvent| match event {
@@ -262,6 +424,43 @@ async fn start<A: Assets>(
 				worlds_bar.clear_cache();
 			}
 			SignalsEvent::PlaceWorld(wid) => tool = Tool::PlaceForeign(wid),
+			SignalsEvent::SetClockPeriod { coords, period } => {
+				game.start_edit();
+				if let Some(main) = game.worlds.at_mut(game.main_id) {
+					main.map_at(coords.0, coords.1, |block| match block {
+						world::Block::Clock { phase, .. } => world::Block::Clock { period, phase },
+						block => block,
+					});
+				}
+				game.finish_edit();
+			}
+			SignalsEvent::SetPinInfo { kind, id, info } => {
+				game.start_edit();
+				if let Some(main) = game.worlds.at_mut(game.main_id) {
+					main.pins_mut().set(kind, id, info);
+				}
+				game.finish_edit();
+			}
+			SignalsEvent::SetAnnotation { coords, text } => {
+				game.start_edit();
+				if let Some(main) = game.worlds.at_mut(game.main_id) {
+					main.set_annotation(coords.0, coords.1, text);
+				}
+				game.finish_edit();
+			}
+			SignalsEvent::Waveform(command) => waveform.apply(command, &game),
+			SignalsEvent::SetProbe { coords, name } => {
+				game.start_edit();
+				if let Some(main) = game.worlds.at_mut(game.main_id) {
+					match name {
+						Some(name) => main.set_probe(coords.0, coords.1, name),
+						None => {
+							main.remove_probe(coords.0, coords.1);
+						}
+					}
+				}
+				game.finish_edit();
+			}
 
 			SignalsEvent::Multiple(_) => {
 				eprintln!("SignalsEvent::Multiple got into inner event handler function")
@@ -389,3 +588,18 @@ fn rl() -> (RaylibHandle, RaylibThread, impl Assets) {
 
 	(rl, thread, asset_provider::Log::new(assets))
 }
+
+/// the dialog for editing the info of a pin in the main world
+fn pin_dialog(
+	game: &Game,
+	kind: world::PinKind,
+	id: usize,
+	at: (i32, i32),
+) -> sui::dialog::Command {
+	let info = game
+		.main()
+		.and_then(|main| main.pins().get(kind, id))
+		.cloned()
+		.unwrap_or_default();
+	ui::dialogs::pin_dialog(kind, id, info, at)
+}
@@ -4,21 +4,83 @@ use foreign_clump::FindClump;
 
 use crate::{
 	game::{Game, WorldId},
-	world::{Block, Direction},
+	world::{Block, Direction, DisplayMode, Received},
 };
 
 pub const TOOLS: &[(&str, Tool)] = &[
 	("place wire", Tool::PlaceWire { start: None }),
+	(
+		"place bus",
+		Tool::PlaceBus {
+			start: None,
+			width: 8,
+		},
+	),
+	(
+		"place splitter",
+		Tool::Place(Block::Splitter(Direction::Right, 0)),
+	),
+	(
+		"place merger",
+		Tool::Place(Block::Merger(Direction::Right, 0)),
+	),
 	("place switch", Tool::Place(Block::Switch(false))),
 	("place junction", Tool::Place(Block::Junction)),
 	("place router", Tool::Place(Block::Router)),
 	("place not", Tool::Place(Block::Not(false))),
+	(
+		"place delay",
+		Tool::Place(Block::Delay(Direction::Right, 2, 0)),
+	),
+	(
+		"place latch",
+		Tool::Place(Block::Latch(Direction::Right, Received::NONE, false)),
+	),
+	(
+		"place flip-flop",
+		Tool::Place(Block::FlipFlop(
+			Direction::Right,
+			Received::NONE,
+			false,
+			false,
+		)),
+	),
+	(
+		"place clock",
+		Tool::Place(Block::Clock {
+			period: 10,
+			phase: 0,
+		}),
+	),
+	(
+		"place button",
+		Tool::Place(Block::Button {
+			pulse: false,
+			pressed: false,
+		}),
+	),
+	(
+		"place pulse button",
+		Tool::Place(Block::Button {
+			pulse: true,
+			pressed: false,
+		}),
+	),
+	(
+		"place 7-segment",
+		Tool::PlaceDisplay(DisplayMode::SevenSegment),
+	),
+	("place hex display", Tool::PlaceDisplay(DisplayMode::Hex)),
+	("place transmitter", Tool::Place(Block::Transmitter(0))),
+	("place receiver", Tool::Place(Block::Receiver(0))),
 	("place input", Tool::PlaceInput),
 	("place output", Tool::PlaceOutput),
 	("remove", Tool::Place(Block::Nothing)),
 	("rotate", Tool::Rotate),
 	("copy", Tool::Copy),
 	("move", Tool::Move),
+	("annotate", Tool::Annotate),
+	("probe", Tool::Probe),
 	("interact", Tool::Interact),
 ];
 
@@ -37,19 +99,33 @@ macro_rules! main_or_return {
 	}};
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub enum Tool {
 	PlaceWire {
 		start: Option<(i32, i32)>,
 	},
+	PlaceBus {
+		start: Option<(i32, i32)>,
+		width: u8,
+	},
 	Place(Block),
 	PlaceInput,
 	PlaceOutput,
 	PlaceForeign(WorldId), // world id
+	PlaceDisplay(DisplayMode),
 
 	Rotate,
 	Copy,
+	/// what the copy tool picked up, placing the annotation it had along with the block
+	Copied {
+		tool: Box<Tool>,
+		annotation: String,
+	},
 	Move,
+	/// opens a dialog to edit the annotation at the clicked coords, handled in main
+	Annotate,
+	/// opens a dialog to name or remove the probe at the clicked coords, handled in main
+	Probe,
 	Moving {
 		// moving
 		// we want the block the user is hovering over to be the block being moved
@@ -58,6 +134,10 @@ pub enum Tool {
 		hovering_over: Block,
 		from: (i32, i32),
 	},
+	/// holding down a button with the interact tool
+	Pressing {
+		at: (i32, i32),
+	},
 
 	//
 	#[default]
@@ -78,6 +158,13 @@ impl Tool {
 
 	pub fn down(&mut self, x: i32, y: i32, game: &mut Game) {
 		match self {
+			Self::Copied { tool, annotation } => {
+				tool.down(x, y, game);
+				// only placing a plain block happens while dragging, everything else places when pressed
+				if let Tool::Place(_) = **tool {
+					main_or_return!(mut game).set_annotation(x, y, annotation.clone());
+				}
+			}
 			Self::Place(block) => {
 				let main = main_or_return!(game);
 				match main.at(x, y) {
@@ -90,6 +177,17 @@ impl Tool {
 							"failed to regenerate moves after replacing a foreign block:\n{err}"
 						),
 					},
+					Some(&Block::Display(mode, pin)) => {
+						// remove the whole display, not just this pin
+						let (px, py) = DisplayMode::pin_offset(pin);
+						let main = main_or_return!(mut game);
+						for ((rx, ry), _) in mode.footprint() {
+							let (x, y) = (x - px + rx, y - py + ry);
+							if let Some(Block::Display(..)) = main.at(x, y) {
+								*main.mut_at(x, y) = Block::Nothing;
+							}
+						}
+					}
 					_ => (),
 				}
 				let main = main_or_return!(mut game);
@@ -104,29 +202,58 @@ impl Tool {
 					let main = main_or_return!(mut game);
 					let moving = std::mem::replace(main.mut_at(from.0, from.1), *hovering_over);
 					let new_hover = std::mem::replace(main.mut_at(x, y), moving);
+					main.swap_annotations(*from, (x, y));
+					main.swap_probes(*from, (x, y));
 
 					*hovering_over = new_hover;
 					*from = (x, y);
 				}
 			}
+			// the button is only pressed while the mouse is over it
+			Self::Pressing { at } => set_button_pressed(game, *at, *at == (x, y)),
 			_ => {}
 		}
 	}
 	pub fn pressed(&mut self, x: i32, y: i32, game: &mut Game) {
+		if let Self::Copied { tool, annotation } = self {
+			tool.pressed(x, y, game);
+			if !matches!(**tool, Tool::Place(_)) {
+				main_or_return!(mut game).set_annotation(x, y, annotation.clone());
+			}
+			return;
+		}
 		let main = main_or_return!(mut game);
 		match self {
 			Self::Rotate => main.map_at(x, y, |i| match i {
 				Block::Wire(dir) => Block::Wire(dir.rotate_r()),
+				Block::Delay(dir, ticks, held) => Block::Delay(dir.rotate_r(), ticks, held),
+				Block::Bus(dir, width) => Block::Bus(dir.rotate_r(), width),
+				Block::Splitter(dir, bit) => Block::Splitter(dir.rotate_r(), bit),
+				Block::Merger(dir, bit) => Block::Merger(dir.rotate_r(), bit),
+				Block::Latch(dir, received, stored) => {
+					Block::Latch(dir.rotate_r(), received, stored)
+				}
+				Block::FlipFlop(dir, received, prev_clock, stored) => {
+					Block::FlipFlop(dir.rotate_r(), received, prev_clock, stored)
+				}
 				_ => i,
 			}),
 			Self::Copy => {
-				*self = match main.at(x, y).copied().unwrap_or_default() {
+				let tool = match main.at(x, y).copied().unwrap_or_default() {
 					Block::Input(_) => Tool::PlaceInput,
 					Block::Output(_) => Tool::PlaceOutput,
 					Block::Foreign(wid, _, _) => Tool::PlaceForeign(wid),
+					Block::Display(mode, _) => Tool::PlaceDisplay(mode),
 
 					block => Tool::Place(block),
-				}
+				};
+				*self = match main.annotation(x, y) {
+					Some(annotation) => Tool::Copied {
+						tool: Box::new(tool),
+						annotation: annotation.to_string(),
+					},
+					None => tool,
+				};
 			}
 			Self::Move => {
 				*self = Tool::Moving {
@@ -134,8 +261,18 @@ impl Tool {
 					from: (x, y),
 				}
 			}
-			Self::PlaceWire { start } if *start == None => *start = Some((x, y)),
-			Self::Interact => main.mut_at(x, y).interact(),
+			Self::PlaceWire { start } | Self::PlaceBus { start, .. } if *start == None => {
+				*start = Some((x, y))
+			}
+			Self::Interact => match main.at(x, y).copied() {
+				Some(Block::Button { pulse, .. }) => {
+					set_button_pressed(game, (x, y), true);
+					if !pulse {
+						*self = Tool::Pressing { at: (x, y) };
+					}
+				}
+				_ => main.mut_at(x, y).interact(),
+			},
 			Self::PlaceInput => {
 				*main.mut_at(x, y) = Block::Input(main.inputs_count());
 				main.io_blocks_fix();
@@ -148,6 +285,11 @@ impl Tool {
 				// TODO if io_blocks_outputs_len() worked properly we wouldn't need to fix io blocks
 				// immediately afterwards
 			}
+			Self::PlaceDisplay(mode) => {
+				for ((rx, ry), pin) in mode.footprint() {
+					*main.mut_at(x + rx, y + ry) = Block::Display(*mode, pin);
+				}
+			}
 			Self::PlaceForeign(wid) => {
 				// rewrite this
 				// cause this doesn't work
@@ -233,9 +375,17 @@ impl Tool {
 		}
 	}
 	pub fn released(&mut self, x: i32, y: i32, game: &mut Game) {
+		if let Self::Copied { tool, .. } = self {
+			tool.released(x, y, game);
+			return;
+		}
 		let main = main_or_return!(mut game);
+		let bus_width = match self {
+			Self::PlaceBus { width, .. } => Some(*width),
+			_ => None,
+		};
 		match self {
-			Self::PlaceWire { start } => {
+			Self::PlaceWire { start } | Self::PlaceBus { start, .. } => {
 				if let Some(start) = start {
 					let x_diff = x - start.0;
 					let y_diff = y - start.1;
@@ -270,10 +420,13 @@ impl Tool {
 
 						let existing = main.at(x, y);
 
-						let new = match existing {
-							Some(Block::Junction) => Block::Junction,
-							Some(Block::Wire(e_dir)) if !dir.is_axis_same(e_dir) => Block::Junction,
-							_ => Block::Wire(dir),
+						let new = match (bus_width, existing) {
+							(Some(width), _) => Block::Bus(dir, width),
+							(None, Some(Block::Junction)) => Block::Junction,
+							(None, Some(Block::Wire(e_dir))) if !dir.is_axis_same(e_dir) => {
+								Block::Junction
+							}
+							(None, _) => Block::Wire(dir),
 						};
 						*main.mut_at(x, y) = new;
 					}
@@ -281,7 +434,23 @@ impl Tool {
 				*start = None;
 			}
 			Tool::Moving { .. } => *self = Tool::Move,
+			Tool::Pressing { at } => {
+				set_button_pressed(game, *at, false);
+				*self = Tool::Interact;
+			}
 			_ => {}
 		}
 	}
 }
+
+/// presses or releases the button at the given coords in the main world \
+/// does nothing if there's no button there
+fn set_button_pressed(game: &mut Game, (x, y): (i32, i32), pressed: bool) {
+	let pulse = match game.main().and_then(|main| main.at(x, y)) {
+		Some(&Block::Button { pulse, .. }) => pulse,
+		_ => return,
+	};
+	if let Some(state) = game.moves.state_mut() {
+		*state.mut_at(x, y) = Some(Block::Button { pulse, pressed });
+	}
+}
@@ -0,0 +1,216 @@
+// dialogs for editing blocks in the world
+
+use sui::{
+	comp::*,
+	core::Store,
+	form::{typable::TypableData, UniqueId},
+	LayableExt,
+};
+
+use crate::{
+	ui::SignalsEvent,
+	world::{PinInfo, PinKind},
+};
+
+/// returns the command that opens a dialog to edit the period of the clock at `coords` \
+/// `at` is where the dialog should appear on the screen
+pub fn clock_dialog(coords: (i32, i32), period: u32, at: (i32, i32)) -> sui::dialog::Command {
+	let text_store = Store::new(TypableData {
+		uid: UniqueId::new(),
+		text: format!("{period}"),
+	});
+	let textbox = sui::form::textbox(text_store.clone(), 16);
+
+	let set = Text::new("set", 12).clickable(move |_| {
+		let period = text_store.with_borrow(|a| a.text.trim().parse::<u32>());
+		match period {
+			Ok(period) if period > 0 => SignalsEvent::Multiple(vec![
+				SignalsEvent::SetClockPeriod { coords, period },
+				SignalsEvent::DialogCommand(sui::dialog::Command::Close),
+			]),
+			_ => {
+				eprintln!("clock period has to be a positive whole number");
+				SignalsEvent::DialogFallback
+			}
+		}
+	});
+	let close = Text::new("close", 12)
+		.clickable(|_| SignalsEvent::DialogCommand(sui::dialog::Command::Close))
+		.to_right();
+	let actions = Overlay::new(set, close);
+
+	let dialog_content = sui::div([
+		sui::custom(Text::new("clock period (ticks)", 16).centered()),
+		sui::custom(textbox),
+		sui::custom(Space::new(0, 10)),
+		sui::custom(actions),
+	]);
+
+	sui::dialog::Command::Open(sui::dialog::Instance {
+		comp: sui::custom(dialog_content),
+		at,
+		scale: 1.0,
+	})
+}
+
+/// returns the command that opens a dialog to edit the annotation at `coords` \
+/// setting an empty text removes the annotation
+pub fn annotation_dialog(coords: (i32, i32), text: String, at: (i32, i32)) -> sui::dialog::Command {
+	let text_store = Store::new(TypableData {
+		uid: UniqueId::new(),
+		text,
+	});
+	let textbox = sui::form::textbox(text_store.clone(), 16);
+
+	let set = Text::new("set", 12).clickable(move |_| {
+		let text = text_store.with_borrow(|a| a.text.clone());
+		SignalsEvent::Multiple(vec![
+			SignalsEvent::SetAnnotation { coords, text },
+			SignalsEvent::DialogCommand(sui::dialog::Command::Close),
+		])
+	});
+	let close = Text::new("close", 12)
+		.clickable(|_| SignalsEvent::DialogCommand(sui::dialog::Command::Close))
+		.to_right();
+	let actions = Overlay::new(set, close);
+
+	let dialog_content = sui::div([
+		sui::custom(Text::new("annotation", 16).centered()),
+		sui::custom(textbox),
+		sui::custom(Space::new(0, 10)),
+		sui::custom(actions),
+	]);
+
+	sui::dialog::Command::Open(sui::dialog::Instance {
+		comp: sui::custom(dialog_content),
+		at,
+		scale: 1.0,
+	})
+}
+
+/// returns the command that opens a dialog to name the probe at `coords`, or to remove it
+pub fn probe_dialog(coords: (i32, i32), name: String, at: (i32, i32)) -> sui::dialog::Command {
+	let text_store = Store::new(TypableData {
+		uid: UniqueId::new(),
+		text: name,
+	});
+	let textbox = sui::form::textbox(text_store.clone(), 16);
+
+	let set = Text::new("set", 12).clickable(move |_| {
+		let name = text_store.with_borrow(|a| a.text.trim().to_string());
+		SignalsEvent::Multiple(vec![
+			SignalsEvent::SetProbe {
+				coords,
+				name: Some(name),
+			},
+			SignalsEvent::DialogCommand(sui::dialog::Command::Close),
+		])
+	});
+	let remove = Text::new("remove", 12)
+		.clickable(move |_| {
+			SignalsEvent::Multiple(vec![
+				SignalsEvent::SetProbe { coords, name: None },
+				SignalsEvent::DialogCommand(sui::dialog::Command::Close),
+			])
+		})
+		.centered();
+	let close = Text::new("close", 12)
+		.clickable(|_| SignalsEvent::DialogCommand(sui::dialog::Command::Close))
+		.to_right();
+	let actions = Overlay::new(Overlay::new(set, remove), close);
+
+	let dialog_content = sui::div([
+		sui::custom(Text::new("probe", 16).centered()),
+		sui::custom(textbox),
+		sui::custom(Space::new(0, 10)),
+		sui::custom(actions),
+	]);
+
+	sui::dialog::Command::Open(sui::dialog::Instance {
+		comp: sui::custom(dialog_content),
+		at,
+		scale: 1.0,
+	})
+}
+
+/// returns the command that opens a dialog to edit the name, color and description of a pin \
+/// colors are written as `#rrggbb`, leaving the color or description empty removes it
+pub fn pin_dialog(kind: PinKind, id: usize, info: PinInfo, at: (i32, i32)) -> sui::dialog::Command {
+	let text_store = |text: String| {
+		Store::new(TypableData {
+			uid: UniqueId::new(),
+			text,
+		})
+	};
+	let name_store = text_store(info.name);
+	let color_store = text_store(
+		info.color
+			.map(|(r, g, b)| format!("#{r:02x}{g:02x}{b:02x}"))
+			.unwrap_or_default(),
+	);
+	let description_store = text_store(info.description.unwrap_or_default());
+
+	let fields = sui::div([
+		sui::custom(Text::new("name", 12)),
+		sui::custom(sui::form::textbox(name_store.clone(), 16)),
+		sui::custom(Text::new("color", 12)),
+		sui::custom(sui::form::textbox(color_store.clone(), 16)),
+		sui::custom(Text::new("description", 12)),
+		sui::custom(sui::form::textbox(description_store.clone(), 16)),
+	]);
+
+	let set = Text::new("set", 12).clickable(move |_| {
+		let color = color_store.with_borrow(|a| parse_color(&a.text));
+		let color = match color {
+			Ok(a) => a,
+			Err(_) => {
+				eprintln!("pin colors have to be written as #rrggbb");
+				return SignalsEvent::DialogFallback;
+			}
+		};
+		let description = description_store.with_borrow(|a| a.text.trim().to_string());
+		let info = PinInfo {
+			name: name_store.with_borrow(|a| a.text.trim().to_string()),
+			color,
+			description: (!description.is_empty()).then_some(description),
+		};
+		SignalsEvent::Multiple(vec![
+			SignalsEvent::SetPinInfo { kind, id, info },
+			SignalsEvent::DialogCommand(sui::dialog::Command::Close),
+		])
+	});
+	let close = Text::new("close", 12)
+		.clickable(|_| SignalsEvent::DialogCommand(sui::dialog::Command::Close))
+		.to_right();
+	let actions = Overlay::new(set, close);
+
+	let title = match kind {
+		PinKind::Input => format!("input {id}"),
+		PinKind::Output => format!("output {id}"),
+	};
+	let dialog_content = sui::div([
+		sui::custom(Text::new(title, 16).centered()),
+		sui::custom(fields),
+		sui::custom(Space::new(0, 10)),
+		sui::custom(actions),
+	]);
+
+	sui::dialog::Command::Open(sui::dialog::Instance {
+		comp: sui::custom(dialog_content),
+		at,
+		scale: 1.0,
+	})
+}
+
+/// parses `#rrggbb` (the # is optional), an empty text is no color
+fn parse_color(text: &str) -> Result<Option<(u8, u8, u8)>, ()> {
+	let hex = text.trim().trim_start_matches('#');
+	if hex.is_empty() {
+		return Ok(None);
+	}
+	if hex.len() != 6 {
+		return Err(());
+	}
+	let rgb = u32::from_str_radix(hex, 16).map_err(|_| ())?;
+	Ok(Some(((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)))
+}
@@ -1,4 +1,6 @@
+pub mod dialogs;
 pub mod ingame;
+pub mod waveform;
 
 pub mod worlds_bar;
 use fit::scrollable::ScrollableState;
@@ -11,6 +13,7 @@ use sui::{
 use crate::{
 	game::{IngameWorld, IngameWorldType, WorldId},
 	processor,
+	world::{PinInfo, PinKind},
 };
 use sui::{comp::*, core::Store, LayableExt};
 
@@ -28,6 +31,26 @@ pub enum SignalsEvent {
 	PlaceWorld(WorldId),
 	WorldsBarFallback,
 
+	SetClockPeriod {
+		coords: (i32, i32),
+		period: u32,
+	},
+	SetAnnotation {
+		coords: (i32, i32),
+		text: String,
+	},
+	/// none removes the probe
+	SetProbe {
+		coords: (i32, i32),
+		name: Option<String>,
+	},
+	SetPinInfo {
+		kind: PinKind,
+		id: usize,
+		info: PinInfo,
+	},
+	Waveform(waveform::WaveformCommand),
+
 	Multiple(Vec<SignalsEvent>),
 }
 impl From<sui::form::FocusCommand> for SignalsEvent {
@@ -132,11 +155,20 @@ pub fn inst_comp(game: &crate::Game, world_id: WorldId) -> sui::Comp<'static> {
 	let insts = processor::world_to_instructions(game, world_id);
 
 	let insts = match insts {
-		Ok(instructions) => {
-			let lines = instructions
+		Ok(compiled) => {
+			let bits = Text::new(
+				format!(
+					"{} instructions, {} bits of memory",
+					compiled.insts.len(),
+					compiled.bits
+				),
+				16,
+			);
+			let lines = compiled
+				.insts
 				.into_iter()
 				.map(|inst| Text::new(format!("{inst:?}"), 16));
-			let lines = lines.collect::<Vec<_>>();
+			let lines = std::iter::once(bits).chain(lines).collect::<Vec<_>>();
 
 			sui::custom(Div::new(false, false, lines))
 		}
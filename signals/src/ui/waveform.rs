@@ -0,0 +1,314 @@
+// a panel with the timing diagram of every probe of the main world, see signals_core::trace
+// it can be paused and scrolled without stopping the simulation, and clicking the ruler places cursors
+
+use fit::scrollable::ScrollableState;
+use raylib::prelude::RaylibDraw;
+use sui::{comp::*, core::Store, Layable, LayableExt};
+
+use crate::{game::Game, gfx, ui::SignalsEvent};
+use signals_core::trace::Trace;
+
+/// how many ticks the panel shows at once
+pub const TICKS_SHOWN: u64 = 300;
+/// how many ticks the arrows scroll by
+const SCROLL_BY: u64 = 50;
+
+const TICK_WIDTH: i32 = 2;
+const ROW_HEIGHT: i32 = 16;
+const RULER_HEIGHT: i32 = 10;
+const NAME_WIDTH: i32 = 80;
+
+pub const PANEL_WIDTH: i32 = NAME_WIDTH + TICKS_SHOWN as i32 * TICK_WIDTH + 10;
+pub const PANEL_HEIGHT: i32 = 220;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WaveformCommand {
+	TogglePause,
+	/// positive is towards the newest tick
+	Scroll(i64),
+	/// unpauses and jumps to the newest tick
+	Live,
+	/// the first two clicks place the two cursors, the one after that starts over
+	Cursor(u64),
+	ClearCursors,
+}
+
+#[derive(Default)]
+pub struct WaveformPanel {
+	/// the ticks the trace had when the panel got paused, the ones recorded after that aren't shown
+	paused: Option<std::ops::Range<u64>>,
+	/// how many ticks before the newest one the view ends, 0 follows the simulation
+	back: u64,
+	cursors: (Option<u64>, Option<u64>),
+	scroll_state: Store<ScrollableState>,
+}
+impl WaveformPanel {
+	pub fn apply(&mut self, command: WaveformCommand, game: &Game) {
+		match command {
+			WaveformCommand::TogglePause => {
+				self.paused = match self.paused {
+					Some(_) => None,
+					None => game.trace.as_ref().map(|trace| trace.start()..trace.end()),
+				}
+			}
+			WaveformCommand::Scroll(by) => {
+				let recorded = game
+					.trace
+					.as_ref()
+					.map(|trace| self.shown(trace))
+					.map(|shown| shown.end - shown.start)
+					.unwrap_or_default();
+				let max_back = recorded.saturating_sub(TICKS_SHOWN);
+				self.back = if by < 0 {
+					self.back + by.unsigned_abs()
+				} else {
+					self.back.saturating_sub(by as u64)
+				}
+				.min(max_back);
+			}
+			WaveformCommand::Live => {
+				self.paused = None;
+				self.back = 0;
+			}
+			WaveformCommand::Cursor(tick) => {
+				self.cursors = match self.cursors {
+					(None, _) => (Some(tick), None),
+					(Some(a), None) => (Some(a), Some(tick)),
+					(Some(_), Some(_)) => (Some(tick), None),
+				}
+			}
+			WaveformCommand::ClearCursors => self.cursors = (None, None),
+		}
+	}
+
+	/// the ticks that can be scrolled through, the ones still kept out of the paused ones if paused
+	fn shown(&self, trace: &Trace) -> std::ops::Range<u64> {
+		match &self.paused {
+			Some(paused) => {
+				let start = paused.start.max(trace.start());
+				start..paused.end.min(trace.end()).max(start)
+			}
+			None => trace.start()..trace.end(),
+		}
+	}
+	/// the ticks currently in view
+	fn window(&self, trace: &Trace) -> std::ops::Range<u64> {
+		let shown = self.shown(trace);
+		let end = shown.end.saturating_sub(self.back).max(shown.start);
+		let start = end.saturating_sub(TICKS_SHOWN).max(shown.start);
+		start..end
+	}
+
+	pub fn comp(&self, game: &Game) -> sui::Comp<'static> {
+		let controls = sui::div_h([
+			sui::custom(
+				Text::new(
+					if self.paused.is_some() {
+						"resume"
+					} else {
+						"pause"
+					},
+					12,
+				)
+				.clickable(move |_| SignalsEvent::Waveform(WaveformCommand::TogglePause))
+				.margin(2),
+			),
+			sui::custom(
+				Text::new("<<", 12)
+					.clickable(move |_| {
+						SignalsEvent::Waveform(WaveformCommand::Scroll(-(SCROLL_BY as i64)))
+					})
+					.margin(2),
+			),
+			sui::custom(
+				Text::new(">>", 12)
+					.clickable(move |_| {
+						SignalsEvent::Waveform(WaveformCommand::Scroll(SCROLL_BY as i64))
+					})
+					.margin(2),
+			),
+			sui::custom(
+				Text::new("live", 12)
+					.clickable(move |_| SignalsEvent::Waveform(WaveformCommand::Live))
+					.margin(2),
+			),
+			sui::custom(
+				Text::new("clear cursors", 12)
+					.clickable(move |_| SignalsEvent::Waveform(WaveformCommand::ClearCursors))
+					.margin(2),
+			),
+		]);
+
+		let trace = match &game.trace {
+			Some(trace) => trace,
+			None => {
+				return sui::custom(
+					sui::div([
+						sui::custom(controls),
+						sui::text(
+							"no probes in this world, place some with the probe tool",
+							12,
+						),
+					])
+					.fix_wh(PANEL_WIDTH, PANEL_HEIGHT),
+				)
+			}
+		};
+		let window = self.window(trace);
+
+		let cursors = match self.cursors {
+			(Some(a), Some(b)) => format!("cursors at {a} and {b}, {} ticks apart", a.abs_diff(b)),
+			(Some(a), None) => format!("cursor at {a}, click again to measure"),
+			_ => "click the ruler to place cursors".to_string(),
+		};
+		let info = Text::new(
+			format!("ticks {}..{}, {cursors}", window.start, window.end),
+			12,
+		);
+
+		// every tick of the ruler places a cursor when clicked
+		let ruler_clicks = window
+			.clone()
+			.map(|tick| {
+				sui::custom(
+					Space::new(TICK_WIDTH, RULER_HEIGHT)
+						.clickable(move |_| SignalsEvent::Waveform(WaveformCommand::Cursor(tick))),
+				)
+			})
+			.collect::<Vec<_>>();
+		let ruler = Overlay::new(
+			Wave::ruler(window.clone(), self.cursors),
+			sui::div_h(ruler_clicks),
+		);
+
+		let rows = trace
+			.probes()
+			.iter()
+			.enumerate()
+			.map(|(i, (_, name))| {
+				sui::custom(sui::div_h([
+					sui::custom(Text::new(name.clone(), 12).fix_wh(NAME_WIDTH, ROW_HEIGHT)),
+					sui::custom(Wave::probe(
+						trace.values(i, window.clone()),
+						window.clone(),
+						self.cursors,
+					)),
+				]))
+			})
+			.collect::<Vec<_>>();
+		let rows = sui::div(rows)
+			.scrollable(self.scroll_state.clone())
+			.fix_wh(PANEL_WIDTH, PANEL_HEIGHT - ROW_HEIGHT * 2 - RULER_HEIGHT);
+
+		let panel = sui::div([
+			sui::custom(controls),
+			sui::custom(info),
+			sui::custom(sui::div_h([
+				Space::new(NAME_WIDTH, RULER_HEIGHT).into_comp(),
+				sui::custom(ruler),
+			])),
+			sui::custom(rows),
+		]);
+		sui::custom(panel.fix_wh(PANEL_WIDTH, PANEL_HEIGHT))
+	}
+}
+
+/// one row of the timing diagram, or the ruler above them if there's no values
+#[derive(Clone, Debug)]
+struct Wave {
+	values: Option<Vec<bool>>,
+	window: std::ops::Range<u64>,
+	cursors: (Option<u64>, Option<u64>),
+}
+impl Wave {
+	fn probe(
+		values: Vec<bool>,
+		window: std::ops::Range<u64>,
+		cursors: (Option<u64>, Option<u64>),
+	) -> Self {
+		Self {
+			values: Some(values),
+			window,
+			cursors,
+		}
+	}
+	fn ruler(window: std::ops::Range<u64>, cursors: (Option<u64>, Option<u64>)) -> Self {
+		Self {
+			values: None,
+			window,
+			cursors,
+		}
+	}
+	fn height(&self) -> i32 {
+		match self.values {
+			Some(_) => ROW_HEIGHT,
+			None => RULER_HEIGHT,
+		}
+	}
+}
+impl Layable for Wave {
+	fn size(&self) -> (i32, i32) {
+		(TICKS_SHOWN as i32 * TICK_WIDTH, self.height())
+	}
+	fn render(&self, d: &mut sui::Handle, det: sui::Details, scale: f32) {
+		let scaled = |a: i32| (a as f32 * scale) as i32;
+		let x_at = |i: usize| det.x + scaled(i as i32 * TICK_WIDTH);
+		let height = scaled(self.height());
+
+		match &self.values {
+			Some(values) => {
+				let y_of = |on: bool| {
+					if on {
+						det.y + scaled(2)
+					} else {
+						det.y + height - scaled(2)
+					}
+				};
+				for (i, on) in values.iter().copied().enumerate() {
+					d.draw_line(
+						x_at(i),
+						y_of(on),
+						x_at(i + 1),
+						y_of(on),
+						if on { gfx::WIRE_ON } else { gfx::WIRE_OFF },
+					);
+					if i > 0 && values[i - 1] != on {
+						d.draw_line(x_at(i), y_of(true), x_at(i), y_of(false), gfx::WIRE_ON);
+					}
+				}
+			}
+			None => {
+				// a mark every 10 ticks, a longer one every 50
+				for (i, tick) in self.window.clone().enumerate() {
+					let len = match tick {
+						tick if tick % 50 == 0 => height,
+						tick if tick % 10 == 0 => height / 2,
+						_ => continue,
+					};
+					d.draw_line(
+						x_at(i),
+						det.y + height - len,
+						x_at(i),
+						det.y + height,
+						sui::comp::text::DEFAULT_COLOR,
+					);
+				}
+			}
+		}
+
+		for cursor in [self.cursors.0, self.cursors.1].into_iter().flatten() {
+			if self.window.contains(&cursor) {
+				let x = x_at((cursor - self.window.start) as usize);
+				d.draw_line(x, det.y, x, det.y + height, gfx::PROBE);
+			}
+		}
+	}
+	fn pass_event(
+		&self,
+		_event: sui::core::Event,
+		_det: sui::Details,
+		_scale: f32,
+	) -> Option<sui::core::ReturnEvent> {
+		None
+	}
+}
@@ -0,0 +1,187 @@
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Context};
+use signals_core::{
+	game::{saves, Game, WorldId, Worlds},
+	processor::{self, batch, Memory},
+	world::Block,
+	Simulator,
+};
+
+const USAGE: &str = "usage:
+  signals-cli list <save>                         id, inputs, outputs and blocks of every world, tab separated
+  signals-cli insts <save> <world>                the instructions the processor runs for the world
+  signals-cli bits <save>                         id, instructions and bits of memory of every world's program, tab separated
+  signals-cli eval <save> <world> [inputs]        the outputs of the world's program for the given inputs
+  signals-cli table <save> <world>                the inputs and outputs of the world's program for every combination of inputs
+  signals-cli sim <save> <world> <ticks> [inputs] the outputs of the world after simulating it for <ticks> ticks
+  signals-cli vcd <save> <world> <ticks> [inputs] the world's probes over <ticks> ticks, as a value change dump
+
+<world> is the id of a world, or any start of it that only one world's id starts with
+[inputs] is a string of 0s and 1s, the first one being input 0. inputs left out are 0
+outputs are printed the same way";
+
+fn main() -> ExitCode {
+	let args = std::env::args().skip(1).collect::<Vec<_>>();
+	let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+	match run(&args) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(err) => {
+			eprintln!("{err:?}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+fn run(args: &[&str]) -> anyhow::Result<()> {
+	match args {
+		["list", save] => {
+			let worlds = saves::read_worlds(save)?;
+			for (wid, world) in worlds.iter() {
+				let blocks = world
+					.blocks()
+					.filter(|(_, block)| **block != Block::Nothing)
+					.count();
+				println!(
+					"{wid}\t{}\t{}\t{blocks}",
+					world.inputs_count(),
+					world.outputs_count()
+				);
+			}
+			Ok(())
+		}
+		["insts", save, world] => {
+			let game = Game::from_worlds(saves::read_worlds(save)?)?;
+			let wid = find_world(&game.worlds, world)?;
+			for inst in processor::world_to_instructions(&game, wid)?.insts {
+				println!("{inst:?}");
+			}
+			Ok(())
+		}
+		["bits", save] => {
+			let game = Game::from_worlds(saves::read_worlds(save)?)?;
+			for (wid, _) in game.worlds.iter() {
+				match processor::world_to_instructions(&game, *wid) {
+					Ok(compiled) => println!("{wid}\t{}\t{}", compiled.insts.len(), compiled.bits),
+					Err(err) => eprintln!("{wid} has no program:\n{err:?}"),
+				}
+			}
+			Ok(())
+		}
+		["eval", save, world, inputs @ ..] if inputs.len() <= 1 => {
+			let game = Game::from_worlds(saves::read_worlds(save)?)?;
+			let wid = find_world(&game.worlds, world)?;
+			let world = game.worlds.at(wid).expect("find_world found it");
+
+			let inputs = parse_bits(inputs.first().copied().unwrap_or(""), world.inputs_count())?;
+			let compiled = processor::world_to_instructions(&game, wid)?;
+
+			let mut memory = Memory::new(compiled.bits);
+			memory.execute(&compiled.insts, &inputs)?;
+			let outputs = (0..world.outputs_count())
+				.map(|i| memory.get(i))
+				.collect::<Vec<_>>();
+			println!("{}", format_bits(&outputs));
+			Ok(())
+		}
+		["table", save, world] => {
+			let game = Game::from_worlds(saves::read_worlds(save)?)?;
+			let wid = find_world(&game.worlds, world)?;
+			let world = game.worlds.at(wid).expect("find_world found it");
+
+			let inputs_len = world.inputs_count();
+			let compiled = processor::world_to_instructions(&game, wid)?;
+			batch::truth_table(
+				&compiled,
+				inputs_len,
+				world.outputs_count(),
+				|row, outputs| {
+					let inputs = (0..inputs_len)
+						.map(|id| row >> id & 1 == 1)
+						.collect::<Vec<_>>();
+					println!("{} {}", format_bits(&inputs), format_bits(outputs));
+				},
+			)
+		}
+		["sim", save, world, ticks, inputs @ ..] if inputs.len() <= 1 => {
+			let sim = simulate(save, world, ticks, inputs.first().copied())?;
+			println!("{}", format_bits(sim.outputs()));
+			Ok(())
+		}
+		["vcd", save, world, ticks, inputs @ ..] if inputs.len() <= 1 => {
+			let sim = simulate(save, world, ticks, inputs.first().copied())?;
+			match &sim.game().trace {
+				Some(trace) => {
+					print!("{}", trace.to_vcd());
+					Ok(())
+				}
+				None => Err(anyhow!("the world doesn't have any probes")),
+			}
+		}
+		_ => Err(anyhow!("{USAGE}")),
+	}
+}
+
+fn simulate(
+	save: &str,
+	world: &str,
+	ticks: &str,
+	inputs: Option<&str>,
+) -> anyhow::Result<Simulator> {
+	let ticks = ticks
+		.parse::<usize>()
+		.with_context(|| format!("{ticks} is not a number of ticks"))?;
+
+	let mut sim = Simulator::read(save)?;
+	let wid = find_world(sim.worlds(), world)?;
+	sim.set_main(wid)?;
+
+	let inputs = parse_bits(inputs.unwrap_or(""), sim.inputs().len())?;
+	for (id, on) in inputs.into_iter().enumerate() {
+		sim.set_input(id, on)?;
+	}
+	sim.step(ticks)?;
+	Ok(sim)
+}
+
+fn find_world(worlds: &Worlds, query: &str) -> anyhow::Result<WorldId> {
+	let matches = worlds
+		.iter()
+		.map(|(wid, _)| *wid)
+		.filter(|wid| wid.to_string().starts_with(query))
+		.collect::<Vec<_>>();
+	match matches.as_slice() {
+		[wid] => Ok(*wid),
+		[] => Err(anyhow!("no world's id starts with {query}")),
+		_ => Err(anyhow!(
+			"{} worlds' ids start with {query}, use more of the id",
+			matches.len()
+		)),
+	}
+}
+
+/// returns exactly `len` bits, filling the ones left out with false
+fn parse_bits(bits: &str, len: usize) -> anyhow::Result<Vec<bool>> {
+	if bits.len() > len {
+		return Err(anyhow!(
+			"got {} inputs, but the world only has {len}",
+			bits.len()
+		));
+	}
+	let mut parsed = bits
+		.chars()
+		.map(|c| match c {
+			'0' => Ok(false),
+			'1' => Ok(true),
+			c => Err(anyhow!("inputs can only be 0 or 1, got {c}")),
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+	parsed.resize(len, false);
+	Ok(parsed)
+}
+fn format_bits(bits: &[bool]) -> String {
+	bits.iter()
+		.map(|bit| if *bit { '1' } else { '0' })
+		.collect()
+}
@@ -0,0 +1,53 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+/// this is the enum that is used to determine what type of a block should be rendered at a position
+pub enum DrawType {
+	#[default]
+	Off,
+	On,
+	/// junction stores values for both axis, they should be reset before every render,
+	/// and entering false won't actually set it to false if it's already true from that same tick
+	Junction {
+		vertical: bool,
+		horizontal: bool,
+	},
+	/// set on pin 0 of a display, every pin that received a signal this tick is a set bit \
+	/// works the same way as junctions, new pins are added to the previous ones
+	Display {
+		pins: u8,
+	},
+}
+impl From<bool> for DrawType {
+	fn from(value: bool) -> Self {
+		if value {
+			Self::On
+		} else {
+			Self::Off
+		}
+	}
+}
+impl DrawType {
+	pub fn apply_new(self, new: Self) -> Self {
+		match new {
+			DrawType::Junction {
+				vertical: new_vert,
+				horizontal: new_horizontal,
+			} => match self {
+				DrawType::Junction {
+					vertical: prev_vert,
+					horizontal: prev_horizontal,
+				} => DrawType::Junction {
+					vertical: prev_vert || new_vert,
+					horizontal: prev_horizontal || new_horizontal,
+				},
+				_ => new,
+			},
+			DrawType::Display { pins: new_pins } => match self {
+				DrawType::Display { pins: prev_pins } => DrawType::Display {
+					pins: prev_pins | new_pins,
+				},
+				_ => new,
+			},
+			_ => new,
+		}
+	}
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+
+use anyhow::Context;
+
+use crate::{game::Game, world::Edit};
+
+/// how many edits of a world can be undone
+pub const MAX_UNDO: usize = 500;
+
+/// the edits of one world, newest last
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct History {
+	undo: VecDeque<Edit>,
+	redo: VecDeque<Edit>,
+}
+impl History {
+	pub fn push(&mut self, edit: Edit) {
+		self.undo.push_back(edit);
+		if self.undo.len() > MAX_UNDO {
+			self.undo.pop_front();
+		}
+		self.redo.clear();
+	}
+	pub fn can_undo(&self) -> bool {
+		!self.undo.is_empty()
+	}
+	pub fn can_redo(&self) -> bool {
+		!self.redo.is_empty()
+	}
+}
+
+impl Game {
+	/// starts recording an edit of the main world, see [crate::world::World::start_edit]
+	pub fn start_edit(&mut self) {
+		if let Some(main) = self.worlds.at_mut(self.main_id) {
+			main.start_edit();
+		}
+	}
+	/// adds everything that changed in the main world since [Game::start_edit] to its history
+	pub fn finish_edit(&mut self) {
+		let edit = match self.worlds.at_mut(self.main_id) {
+			Some(main) => main.finish_edit(),
+			None => None,
+		};
+		if let Some(edit) = edit {
+			if let Some(state) = self.moves.state_mut() {
+				edit.clear_states(state);
+			}
+			self.history.entry(self.main_id).or_default().push(edit);
+		}
+	}
+	pub fn history(&self) -> Option<&History> {
+		self.history.get(&self.main_id)
+	}
+
+	/// undoes the last edit of the main world, returns false if there was nothing to undo
+	pub fn undo(&mut self) -> anyhow::Result<bool> {
+		self.finish_edit();
+		let edit = match self.history.get_mut(&self.main_id).and_then(|h| h.undo.pop_back()) {
+			Some(edit) => edit,
+			None => return Ok(false),
+		};
+		if let Some(main) = self.worlds.at_mut(self.main_id) {
+			main.undo(&edit);
+		}
+		self.after_history_change(edit, |history| &mut history.redo)
+			.with_context(|| "in Game::undo")?;
+		Ok(true)
+	}
+	/// redoes the last undone edit of the main world, returns false if there was nothing to redo
+	pub fn redo(&mut self) -> anyhow::Result<bool> {
+		self.finish_edit();
+		let edit = match self.history.get_mut(&self.main_id).and_then(|h| h.redo.pop_back()) {
+			Some(edit) => edit,
+			None => return Ok(false),
+		};
+		if let Some(main) = self.worlds.at_mut(self.main_id) {
+			main.redo(&edit);
+		}
+		self.after_history_change(edit, |history| &mut history.undo)
+			.with_context(|| "in Game::redo")?;
+		Ok(true)
+	}
+	/// moves the edit over to the other stack, clearing the state of the blocks it changed and regenerating the moves
+	/// if the ios or foreigns changed
+	fn after_history_change(
+		&mut self,
+		edit: Edit,
+		stack: impl FnOnce(&mut History) -> &mut VecDeque<Edit>,
+	) -> anyhow::Result<()> {
+		if let Some(state) = self.moves.state_mut() {
+			edit.clear_states(state);
+		}
+		let changes_io = edit.changes_io();
+		stack(self.history.entry(self.main_id).or_default()).push_back(edit);
+		if changes_io {
+			self.regenerate_moves(self.main_id)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::world::Block;
+
+	use super::*;
+
+	#[test]
+	fn undo_and_redo_edits() {
+		let mut game = Game::default();
+		let wid = game.push();
+		game.main_id = wid;
+
+		// one edit placing two inputs, the second one gets renumbered by io_blocks_fix
+		game.start_edit();
+		let main = game.worlds.at_mut(wid).unwrap();
+		*main.mut_at(0, 0) = Block::Input(0);
+		*main.mut_at(1, 0) = Block::Input(0);
+		main.io_blocks_fix();
+		main.set_annotation(0, 0, "a".to_string());
+		game.finish_edit();
+
+		game.start_edit();
+		*game.worlds.at_mut(wid).unwrap().mut_at(0, 0) = Block::Nothing;
+		game.finish_edit();
+		// nothing changed, nothing to undo
+		game.start_edit();
+		game.finish_edit();
+
+		let main = |game: &Game| game.worlds.at(wid).unwrap().clone();
+		let edited = main(&game);
+		assert_eq!(edited.at(0, 0), Some(&Block::Nothing));
+		assert_eq!(edited.at(1, 0), Some(&Block::Input(1)));
+
+		assert!(game.undo().unwrap());
+		assert_eq!(main(&game).at(0, 0), Some(&Block::Input(0)));
+		assert_eq!(main(&game).at(1, 0), Some(&Block::Input(1)));
+
+		assert!(game.undo().unwrap());
+		assert_eq!(main(&game).at(0, 0), Some(&Block::Nothing));
+		assert_eq!(main(&game).at(1, 0), Some(&Block::Nothing));
+		assert_eq!(main(&game).annotation(0, 0), None);
+		assert!(!game.undo().unwrap());
+
+		assert!(game.redo().unwrap());
+		assert!(game.redo().unwrap());
+		assert!(!game.redo().unwrap());
+		assert_eq!(main(&game), edited);
+	}
+}
@@ -0,0 +1,739 @@
+use anyhow::{anyhow, Context};
+use std::{
+	collections::{HashMap, HashSet},
+	hash::{DefaultHasher, Hasher},
+};
+
+use crate::{
+	drawtype::DrawType,
+	game::{Game, Programs, WorldId, Worlds},
+	processor::{
+		batch::{BatchMemory, LANES},
+		Memory,
+	},
+	world::{Block, BlockError, BlockStates, Move, Signal, World},
+};
+use rayon::prelude::*;
+use std::hash::Hash;
+
+// how are we gonna get a whole world inside a block? i hear you asking
+// create a block that contains: world id, input id, output id
+// for now we can just hardcode for example signals from the left will be treated as input,
+// while all other sides will output the output
+// this is actually feasible but i'm soooooo tired gn
+
+// we need a new architecture this one sucks ass
+// ingame world:
+// - World
+// - moves
+// - children (Vec<ingame world>)
+
+// this makes everything easier let's implement it
+// so long Game struct (it stayed)
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IngameWorldType {
+	Simulated {
+		moves: Vec<Move>,
+		/// the state of this instance's stateful blocks, so instances of the same world don't interfere
+		state: BlockStates,
+	},
+	Processor {
+		inputs: Vec<bool>,
+		prev_in_hash: u64,
+		prev_out: Vec<bool>,
+		/// what the program's state bits held after the last tick, see [crate::processor::Instruction::StoreState]
+		state: Vec<bool>,
+		/// how many ticks this instance has been around for, so its clocks count from when it was made
+		/// like a simulated instance's do, see [crate::processor::Instruction::SummonClock]
+		ticks: u64,
+	},
+}
+impl Default for IngameWorldType {
+	fn default() -> Self {
+		Self::simulated()
+	}
+}
+impl IngameWorldType {
+	pub fn simulated() -> Self {
+		Self::Simulated {
+			moves: vec![],
+			state: Default::default(),
+		}
+	}
+	pub const fn processor() -> Self {
+		Self::Processor {
+			inputs: vec![],
+			prev_in_hash: 0,
+			prev_out: vec![],
+			state: vec![],
+			ticks: 0,
+		}
+	}
+}
+
+/// the parts of [Game] ticking reads, shared between the threads ticking instances
+#[derive(Copy, Clone)]
+struct TickContext<'a> {
+	worlds: &'a Worlds,
+	programs: &'a Programs,
+}
+
+/// IngameWorld represents a world inside of a block, either fully simulated
+/// or calculated on demand
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IngameWorld {
+	pub world_id: WorldId,
+	pub typ: IngameWorldType,
+	pub children: Vec<IngameWorld>,
+}
+impl IngameWorld {
+	/// generates the ingameworld required for a World to have foreigns working \
+	/// see [IngameWorld::regenerate] \
+	/// will return simulated if world_id == game.main_id \
+	/// if you need an ingameworld that is surely simulated, use [IngameWorld::simulated]
+	pub fn generate(game: &mut Game, world_id: WorldId) -> anyhow::Result<Self> {
+		if game.main_id == world_id {
+			return Self::simulated(game, world_id);
+		}
+
+		let typ = {
+			if let Some((Some(_), _, _)) = game.programs.get(&world_id) {
+				IngameWorldType::processor()
+			} else {
+				IngameWorldType::simulated()
+			}
+		};
+		let mut ingameworld = Self {
+			world_id,
+			typ,
+			..Default::default()
+		};
+		ingameworld.regenerate(game, world_id)?;
+		Ok(ingameworld)
+	}
+	/// returns the state of this instance's blocks, if it's simulated
+	pub fn state_mut(&mut self) -> Option<&mut BlockStates> {
+		match &mut self.typ {
+			IngameWorldType::Simulated { state, .. } => Some(state),
+			IngameWorldType::Processor { .. } => None,
+		}
+	}
+	/// hashes what the next tick of this instance and its children depends on besides the blocks of their worlds,
+	/// see [crate::oscillation] \
+	/// a processor's clocks only go in as where they are in their period, so processors with clocks can still repeat
+	pub fn hash_state(&self, programs: &Programs, hasher: &mut impl Hasher) {
+		self.world_id.hash(hasher);
+		match &self.typ {
+			IngameWorldType::Simulated { moves, state } => {
+				moves.hash(hasher);
+				state.hash(hasher);
+			}
+			IngameWorldType::Processor {
+				inputs,
+				prev_in_hash,
+				prev_out,
+				state,
+				ticks,
+			} => {
+				inputs.hash(hasher);
+				prev_in_hash.hash(hasher);
+				prev_out.hash(hasher);
+				state.hash(hasher);
+				let clock_period = programs
+					.get(&self.world_id)
+					.and_then(|(compiled, _, _)| compiled.as_ref())
+					.map(|compiled| compiled.clock_period.max(1))
+					.unwrap_or(1);
+				(ticks % clock_period).hash(hasher);
+			}
+		}
+		for child in &self.children {
+			child.hash_state(programs, hasher);
+		}
+	}
+
+	pub fn simulated(game: &mut Game, world_id: WorldId) -> anyhow::Result<Self> {
+		let mut ingameworld = Self {
+			world_id,
+			typ: IngameWorldType::simulated(),
+			..Default::default()
+		};
+		ingameworld.regenerate(game, world_id)?;
+		Ok(ingameworld)
+	}
+
+	/// regenerates itself and fixes the world if needed \
+	/// recursive \
+	/// will not edit self.typ, make sure you have that set up correctly
+	pub fn regenerate(&mut self, game: &mut Game, world_id: WorldId) -> anyhow::Result<()> {
+		match &mut self.typ {
+			IngameWorldType::Simulated { .. } => {
+				let world = match game.worlds.at(world_id) {
+					Some(a) => a,
+					None => {
+						return Err(anyhow!(
+							"could not find a world with id {world_id}\nneeded for IngameWorld::regenerate"
+						))
+					}
+				};
+				let mut foreigns = world.find_foreigns().collect::<Vec<_>>();
+				foreigns.sort_by(|(_, (_, a_inst_id, a_id)), (_, (_, b_inst_id, b_id))| {
+					(a_inst_id * 1000 + a_id).cmp(&(b_inst_id * 1000 + b_id))
+				});
+
+				// yeah so we have to make sure there's no holes in the inst_ids
+
+				let mut replaced_inst_ids = HashMap::<usize, usize>::new();
+
+				let mut prev_inst_id: i32 = -1;
+				for (coords, (f_wid, inst_id, id)) in foreigns.iter().copied() {
+					if let Some(new_inst_id) = replaced_inst_ids.get(&inst_id) {
+						if let Some(a) = game.worlds.at_mut(f_wid) {
+							*a.mut_at(coords.0, coords.1) =
+								Block::Foreign(f_wid, *new_inst_id as usize, id);
+						}
+						continue;
+					}
+					if prev_inst_id == inst_id as i32 {
+						continue;
+					}
+					if inst_id as i32 == prev_inst_id + 1 {
+						prev_inst_id = inst_id as i32;
+						continue;
+					}
+					if inst_id as i32 > prev_inst_id + 1 {
+						prev_inst_id += 1;
+						replaced_inst_ids.insert(inst_id, prev_inst_id as usize);
+						if let Some(a) = game.worlds.at_mut(f_wid) {
+							*a.mut_at(coords.0, coords.1) =
+								Block::Foreign(f_wid, prev_inst_id as usize, id);
+						}
+					}
+				}
+
+				let foreigns = foreigns.into_iter().map(|(coords, (f_wid, inst_id, id))| {
+					(
+						coords,
+						(
+							f_wid,
+							replaced_inst_ids.get(&inst_id).copied().unwrap_or(inst_id),
+							id,
+						),
+					)
+				});
+
+				let mut inst_ids_already_done = Vec::with_capacity(self.children.len());
+				let mut next_id_per_inst_id: HashMap<usize, usize> = HashMap::new();
+
+				for (coords, (inst_world_id, inst_id, id)) in foreigns {
+					if !inst_ids_already_done.contains(&inst_id) {
+						// only happens once per inst_id
+
+						let inst = match self.children.iter_mut().nth(inst_id) {
+							Some(ptr) => ptr,
+							None => {
+								// generate ingameworld for inst_id
+								// since foreigns is already sorted by inst_id and id, it's a little easier
+								assert_eq!(self.children.len(), inst_id); // <- make sure just pushing it onto self.children will work
+
+								let child = IngameWorld::generate(game, inst_world_id)?;
+								self.children.push(child);
+								&mut self.children[inst_id]
+							}
+						};
+
+						if inst_world_id == world_id {
+							println!("deleting a foreign referencing the world the foreign's in (world_id: {world_id:?})");
+							game.worlds
+								.at_mut(world_id)
+								.with_context(|| {
+									"second impossible case in IngameWorld::regenerate"
+								})?
+								.map_at(coords.0, coords.1, |_| {
+									Block::Error(BlockError::Recursion { inst_id, id })
+								});
+							continue;
+						}
+
+						inst.regenerate(game, inst_world_id)
+							.with_context(|| "error while regenerating child")?;
+						inst_ids_already_done.push(inst_id);
+					}
+
+					let inst_world = match game.worlds.at(inst_world_id) {
+						Some(a) => a,
+						None => {
+							eprintln!("deleting a foreign referencing an invalid world ({inst_world_id}) in {world_id}");
+							// this foreign has an invalid world id
+							*game
+								.worlds
+								.at_mut(world_id)
+								.with_context(|| "impossible case 3 in IngameWorld::regenerate()")?
+								.mut_at(coords.0, coords.1) = Block::Error(BlockError::WorldDoesntExist {
+								wid: inst_world_id,
+								inst_id,
+								id,
+							});
+							return Ok(()); // <- fake Ok
+						}
+					};
+
+					let next = match next_id_per_inst_id.get(&inst_id) {
+						Some(next) => *next,
+						None => 0,
+					};
+					let max_id = inst_world.max_f_id();
+					let world_mut = game
+						.worlds
+						.at_mut(world_id)
+						.with_context(|| "impossible case 3 IngameWorld::regenerate()")?;
+					if id > max_id {
+						eprintln!("the world (id: {world_id:?}) contained a foreign that exceeded the maximum possible id of {max_id} for the world given ({inst_world_id:?}) by being {id}");
+						world_mut.map_at(coords.0, coords.1, |block| {
+							Block::Error(match block {
+								Block::Foreign(wid, inst_id, id) => BlockError::MaxIdExceeded {
+									wid,
+									this_was: id,
+									max_id,
+								},
+								_ => BlockError::Other,
+							})
+						});
+					} else {
+						world_mut.map_at(coords.0, coords.1, |_| {
+							Block::Foreign(inst_world_id, inst_id, next)
+						});
+						next_id_per_inst_id.insert(inst_id, next + 1);
+					}
+				}
+			}
+			IngameWorldType::Processor { inputs, .. } => {
+				if self.children.len() != 0 {
+					self.children = vec![];
+				}
+				if let Some((Some(_), in_len, _)) = game.programs.get(&world_id) {
+					if inputs.len() != *in_len {
+						*inputs = vec![false; *in_len];
+					}
+				} else {
+					// if there's no program for this world, turn self into a simulated
+					self.typ = IngameWorldType::simulated();
+					self.regenerate(game, world_id)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// ticks this world, not its children, see [IngameWorld::tick_children]
+	pub fn tick(
+		&mut self,
+		game: &mut Game,
+		ret: impl FnMut(Move),
+		set_dt: bool,
+	) -> anyhow::Result<()> {
+		self.regenerate_lost_programs(game)?;
+
+		let Game {
+			worlds,
+			programs,
+			drawmap,
+			..
+		} = game;
+		let ctx = TickContext { worlds, programs };
+		self.tick_in(ctx, if set_dt { Some(drawmap) } else { None }, ret)
+	}
+	fn tick_in(
+		&mut self,
+		ctx: TickContext,
+		drawmap: Option<&mut World<DrawType>>,
+		mut ret: impl FnMut(Move),
+	) -> anyhow::Result<()> {
+		match &mut self.typ {
+			IngameWorldType::Simulated { moves, state } => {
+				let new_moves = ctx.worlds.at(self.world_id).with_context(|| {
+					format!("this IngameWorld points to a nonexistent world\nworld_id: {:?}\ntyp: {:?}\nchildren: {:#?}", self.world_id, "IngameWorldType::Simulated", self.children)
+				})?;
+				let new_moves = match drawmap {
+					Some(drawmap) => new_moves.tick(state, std::mem::take(moves), |x, y, dt| {
+						*drawmap.mut_at(x, y) =
+							drawmap.at(x, y).copied().unwrap_or_default().apply_new(dt);
+					}),
+					None => new_moves.tick(state, std::mem::take(moves), |_, _, _| {}),
+				};
+				self.process_moves(new_moves, ret);
+			}
+			IngameWorldType::Processor {
+				inputs,
+				prev_in_hash,
+				prev_out,
+				state,
+				ticks,
+			} => {
+				let (compiled, out_len) = match ctx.programs.get(&self.world_id) {
+					Some((Some(compiled), _, out_len)) => (compiled, out_len),
+					_ => {
+						return Err(anyhow!(
+							"no program for processor {}, IngameWorld::regenerate_lost_programs should've made it simulated",
+							self.world_id
+						))
+					}
+				};
+
+				let mut in_hash = DefaultHasher::new();
+				inputs.hash(&mut in_hash);
+				let in_hash = in_hash.finish();
+
+				if in_hash != *prev_in_hash || compiled.changes_by_itself {
+					// every processor gets its own memory so they can run on different threads
+					let mut memory = Memory::new(compiled.bits);
+					memory.set_tick(*ticks);
+					memory.set_state(std::mem::take(state));
+					let ran = memory.execute(&compiled.insts, inputs);
+					*state = memory.take_state();
+					ran.with_context(|| format!("while running processor {}", self.world_id))?;
+					for i in 0..*out_len {
+						if memory.get(i) {
+							ret(Move::Output {
+								id: i,
+								signal: Signal::Default,
+							});
+						}
+					}
+
+					*prev_in_hash = in_hash;
+					*prev_out = memory[0..*out_len].to_vec();
+				} else {
+					for (i, val) in prev_out.iter().enumerate() {
+						if *val {
+							ret(Move::Output {
+								id: i,
+								signal: Signal::Default,
+							})
+						}
+					}
+				}
+
+				*inputs = inputs.into_iter().map(|_| false).collect();
+				*ticks += 1;
+			}
+		}
+		Ok(())
+	}
+	/// a processor whose world lost its program turns itself into a simulated world, which needs the whole game \
+	/// so that's done before ticking, and ticking itself only has to read the game \
+	/// recursive
+	fn regenerate_lost_programs(&mut self, game: &mut Game) -> anyhow::Result<()> {
+		if let IngameWorldType::Processor { .. } = self.typ {
+			if !matches!(game.programs.get(&self.world_id), Some((Some(_), _, _))) {
+				self.regenerate(game, self.world_id).with_context(|| {
+					format!("while regenerating ingameworld for {}", self.world_id)
+				})?;
+			}
+		}
+		for child in self.children.iter_mut() {
+			child.regenerate_lost_programs(game)?;
+		}
+		Ok(())
+	}
+
+	/// ticks every child and their children, in parallel \
+	/// expects [IngameWorld::tick] to have been called this tick
+	pub(crate) fn tick_children(&mut self, game: &mut Game) -> anyhow::Result<()> {
+		self.tick_children_in(TickContext {
+			worlds: &game.worlds,
+			programs: &game.programs,
+		})
+	}
+	fn tick_children_in(&mut self, ctx: TickContext) -> anyhow::Result<()> {
+		match &mut self.typ {
+			IngameWorldType::Simulated { moves, .. } => {
+				let world_id = self.world_id;
+				let batched = tick_processors_batched(&mut self.children, ctx)
+					.with_context(|| format!("in children of world_id: {world_id:?}"))?;
+				// children don't affect each other during a tick, only their outputs have to be collected
+				// in the same order as if they were ticked one by one
+				let outputs = self
+					.children
+					.par_iter_mut()
+					.zip(batched.into_par_iter())
+					.enumerate()
+					.map(|(i, (child, batched))| {
+						if let Some(outputs) = batched {
+							return Ok(outputs);
+						}
+						let mut outputs = vec![];
+						child
+							.tick_in(ctx, None, |m| match m {
+								Move::Output { id, .. } => outputs.push(id),
+								mov => {
+									eprintln!(
+										"only outputs should be returned from child worlds ({mov:?})"
+									)
+								}
+							})
+							.with_context(|| {
+								format!("from #{i} child of world_id: {world_id:?}")
+							})?;
+						child.tick_children_in(ctx)?;
+						Ok(outputs)
+					})
+					.collect::<anyhow::Result<Vec<_>>>()?;
+
+				for (i, outputs) in outputs.into_iter().enumerate() {
+					moves.extend(outputs.into_iter().map(|id| Move::Foreign {
+						inst_id: i,
+						id,
+						signal: Signal::ExternalPoweron,
+					}));
+				}
+			}
+			IngameWorldType::Processor { .. } => {}
+		}
+		Ok(())
+	}
+
+	pub(super) fn process_moves(&mut self, new_moves: Vec<Move>, mut ret: impl FnMut(Move)) {
+		match &mut self.typ {
+			IngameWorldType::Simulated { moves, .. } => {
+				*moves = Vec::with_capacity(new_moves.len());
+
+				// collected first so every world only has to dedup once
+				let mut inside = Vec::with_capacity(new_moves.len());
+				let mut to_children = HashMap::<usize, Vec<Move>>::new();
+				for mov in new_moves {
+					match mov {
+						Move::Inside { .. } | Move::Channel { .. } => inside.push(mov),
+						Move::Output { .. } => ret(mov),
+						Move::Foreign { inst_id, id, .. } => {
+							to_children.entry(inst_id).or_default().push(Move::Input {
+								id,
+								signal: Signal::ExternalPoweron,
+							})
+						}
+						Move::Input { .. } => {
+							eprintln!("unexpected input move in moves processing: {mov:?}")
+						}
+					}
+				}
+
+				self.receive_moves(inside);
+				for (inst_id, moves) in to_children {
+					if let Some(child) = self.children.get_mut(inst_id) {
+						child.receive_moves(moves);
+					}
+				}
+			}
+			IngameWorldType::Processor { .. } => {}
+		}
+	}
+	/// receive moves from parent or self, with dedup and everything handled inside
+	pub fn receive_moves(&mut self, new_moves: impl IntoIterator<Item = Move>) {
+		match &mut self.typ {
+			IngameWorldType::Simulated { moves, .. } => {
+				let new_moves = new_moves.into_iter();
+				moves.reserve(new_moves.size_hint().0);
+
+				let mut seen = moves.iter().cloned().collect::<HashSet<_>>();
+				for mov in new_moves {
+					if seen.insert(mov.clone()) {
+						moves.push(mov);
+					}
+				}
+			}
+			IngameWorldType::Processor { inputs, .. } => {
+				for mov in new_moves {
+					match mov {
+						Move::Input { id, .. } => {
+							if inputs.len() - 1 < id {
+								inputs.resize(id + 1, false);
+							}
+							inputs[id] = true;
+						}
+						_ => eprintln!(
+							"dropping non-input move to a a processor ingameworld:\n{mov:#?}"
+						),
+					}
+				}
+				0;
+			}
+		}
+	}
+}
+
+/// runs processors of the same world that have been around for as long together, [LANES] at a time in a [BatchMemory] \
+/// returns the outputs of every child it ran, the others are left for [IngameWorld::tick_in]
+fn tick_processors_batched(
+	children: &mut [IngameWorld],
+	ctx: TickContext,
+) -> anyhow::Result<Vec<Option<Vec<usize>>>> {
+	let mut ran = vec![None; children.len()];
+	// K: (world_id, ticks), every lane runs at the same tick
+	let mut by_world = HashMap::<(WorldId, u64), Vec<usize>>::new();
+	for (i, child) in children.iter().enumerate() {
+		if let IngameWorldType::Processor { ticks, .. } = child.typ {
+			by_world.entry((child.world_id, ticks)).or_default().push(i);
+		}
+	}
+
+	for ((world_id, ticks), indices) in by_world {
+		// a lone processor is better off skipping runs when its inputs don't change
+		if indices.len() < 2 {
+			continue;
+		}
+		// not having a program is an error IngameWorld::tick_in already reports
+		let Some((Some(compiled), _, out_len)) = ctx.programs.get(&world_id) else {
+			continue;
+		};
+
+		for batch in indices.chunks(LANES) {
+			let mut inputs = vec![];
+			let mut state = vec![];
+			for (lane, i) in batch.iter().enumerate() {
+				let IngameWorldType::Processor {
+					inputs: lane_inputs,
+					state: lane_state,
+					..
+				} = &children[*i].typ
+				else {
+					unreachable!("only processors get batched")
+				};
+				for (bits, lane_bits) in [(&mut inputs, lane_inputs), (&mut state, lane_state)] {
+					if bits.len() < lane_bits.len() {
+						bits.resize(lane_bits.len(), 0_u64);
+					}
+					for (bit, on) in bits.iter_mut().zip(lane_bits) {
+						*bit |= (*on as u64) << lane;
+					}
+				}
+			}
+
+			let mut memory = BatchMemory::new(compiled.bits);
+			memory.set_tick(ticks);
+			memory.set_state(state);
+			memory
+				.execute(&compiled.insts, &inputs)
+				.with_context(|| format!("while running processors of {world_id}"))?;
+			let state = memory.take_state();
+
+			for (lane, i) in batch.iter().enumerate() {
+				let IngameWorldType::Processor {
+					inputs: lane_inputs,
+					prev_in_hash,
+					prev_out,
+					state: lane_state,
+					ticks: lane_ticks,
+				} = &mut children[*i].typ
+				else {
+					unreachable!("only processors get batched")
+				};
+				let mut in_hash = DefaultHasher::new();
+				lane_inputs.hash(&mut in_hash);
+				*prev_in_hash = in_hash.finish();
+
+				*prev_out = (0..*out_len).map(|id| memory.lane(id, lane)).collect();
+				*lane_state = state.iter().map(|bits| bits >> lane & 1 == 1).collect();
+				*lane_inputs = lane_inputs.iter().map(|_| false).collect();
+				*lane_ticks += 1;
+
+				let outputs = prev_out.iter().enumerate().filter(|(_, on)| **on);
+				ran[*i] = Some(outputs.map(|(id, _)| id).collect());
+			}
+		}
+	}
+	Ok(ran)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// main has an instance of a world with a clock of period 3 wired to its output, main's output 0 being on
+	/// when it pulses \
+	/// the game has already been running for `since` ticks by the time the instance is made
+	fn clock_game(processor: bool, since: u64) -> Game {
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Clock {
+			period: 3,
+			phase: 0,
+		};
+		*world.mut_at(1, 0) = Block::Output(0);
+
+		let main = game.push();
+		let world = game.worlds.at_mut(main).unwrap();
+		*world.mut_at(0, 0) = Block::Foreign(wid, 0, 0);
+		*world.mut_at(1, 0) = Block::Output(0);
+
+		game.main_id = main;
+		game.ticks = since;
+		if processor {
+			game.generate_programs_for(main).unwrap();
+		}
+		game.regenerate_moves(main).unwrap();
+		assert_eq!(
+			matches!(
+				game.moves.children[0].typ,
+				IngameWorldType::Processor { .. }
+			),
+			processor
+		);
+		game
+	}
+
+	/// the ticks main's output 0 is on, counting from when the instance inside was made
+	fn clock_pulses(processor: bool, since: u64) -> Vec<u64> {
+		let mut game = clock_game(processor, since);
+		(0..11)
+			.filter(|_| {
+				let mut on = false;
+				game.tick_with_outputs(|_| on = true).unwrap();
+				on
+			})
+			.collect()
+	}
+
+	#[test]
+	fn processor_clocks_count_from_when_theyre_made() {
+		for processor in [false, true] {
+			let pulses = clock_pulses(processor, 0);
+			assert_eq!(
+				pulses.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>(),
+				[3, 3]
+			);
+			for since in [1, 2, 7] {
+				assert_eq!(clock_pulses(processor, since), pulses);
+			}
+		}
+	}
+
+	#[test]
+	fn processor_clocks_repeat() {
+		for processor in [false, true] {
+			let mut game = clock_game(processor, 0);
+			for _ in 0..20 {
+				game.tick().unwrap();
+			}
+			assert!(
+				matches!(
+					game.oscillation.activity(),
+					crate::oscillation::Activity::Repeating { period: 3, .. }
+				),
+				"{:?}",
+				game.oscillation.activity()
+			);
+
+			// the moves and states are the same as before, but main isn't
+			let main = game.main_id;
+			*game.worlds.at_mut(main).unwrap().mut_at(5, 5) = Block::Router;
+			game.tick().unwrap();
+			assert_eq!(
+				*game.oscillation.activity(),
+				crate::oscillation::Activity::Changing
+			);
+		}
+	}
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+	collections::HashMap,
+	hash::{DefaultHasher, Hash, Hasher},
+};
 
 mod worlds;
 use anyhow::Context;
@@ -7,9 +10,21 @@ pub use worlds::*;
 mod ingameworld;
 pub use ingameworld::*;
 
+mod history;
+pub use history::*;
+
+mod rewind;
+pub use rewind::*;
+
 pub mod saves;
 
-use crate::{gfx::DrawType, processor, world::World};
+use crate::{
+	drawtype::DrawType,
+	oscillation::OscillationDetector,
+	processor,
+	trace::Trace,
+	world::{Move, World},
+};
 
 // ok so foreigns are great but we have a processor system we need to implement
 // game should store the memory and the programs and take care of regenerating them as needed,
@@ -29,11 +44,22 @@ pub struct Game {
 
 	/// moves of self.main
 	pub moves: IngameWorld,
+	/// how many times the game has ticked, processor worlds use it to know where their clocks are at
+	pub ticks: u64,
+	/// what the probes of the main world did, none if it doesn't have any
+	pub trace: Option<Trace>,
+	/// whether the main world settled, is stuck in a loop or is still changing
+	pub oscillation: OscillationDetector,
+	/// edits that can be undone and redone, by world
+	history: HashMap<WorldId, History>,
+	/// the last ticks stepped through while paused, for stepping back through them
+	pub rewind: Rewind,
 
-	memory: processor::Memory,
 	pub programs: Programs,
+	/// what timing programs are generated with, change it with [Game::set_timing]
+	pub timing: processor::Timing,
 }
-type Programs = HashMap<WorldId, (Option<Vec<processor::Instruction>>, usize, usize)>; // v: (none if errored during instgen, inputs_len, outputs_len)
+type Programs = HashMap<WorldId, (Option<processor::Compiled>, usize, usize)>; // v: (none if errored during instgen, inputs_len, outputs_len)
 impl Game {
 	pub fn from_worlds(worlds: Worlds) -> anyhow::Result<Self> {
 		// since the world loads with a nonexistent main_id, it's ok to just use Default::default()
@@ -53,18 +79,38 @@ impl Game {
 	}
 
 	pub fn tick(&mut self) -> anyhow::Result<()> {
+		self.tick_with_outputs(|_id| {
+			// eprintln!("dropping an output returned from game.main ({_id})")
+		})
+	}
+	/// same as [Game::tick], but calls `output` with the id of every output of the main world that got a signal this tick
+	pub fn tick_with_outputs(&mut self, mut output: impl FnMut(usize)) -> anyhow::Result<()> {
+		self.snapshot();
+
 		// reset the drawmap
 		for (_, c) in self.drawmap.chunks_mut() {
 			*c = Default::default();
 		}
 
-		if let Some(_) = self.worlds.at_mut(self.main_id) {
+		if let Some(main) = self.worlds.at(self.main_id) {
 			// test if the world exists
+			if let IngameWorldType::Simulated { moves, .. } = &self.moves.typ {
+				// the main world's blocks only go in as its generation, hashing all of them every tick adds up
+				let mut hasher = DefaultHasher::new();
+				self.main_id.hash(&mut hasher);
+				main.generation().hash(&mut hasher);
+				self.moves.hash_state(&self.programs, &mut hasher);
+				self.oscillation.observe(hasher.finish(), moves);
+			}
+
 			let mut taken_moves = std::mem::take(&mut self.moves);
 			taken_moves.tick(
 				self,
-				|_mov| {
-					// eprintln!("dropping a move returned from game.main ({_mov:?})")
+				|mov| match mov {
+					Move::Output { id, .. } => output(id),
+					mov => {
+						eprintln!("dropping a non-output move returned from game.main ({mov:?})")
+					}
 				},
 				true,
 			)?;
@@ -72,8 +118,30 @@ impl Game {
 			taken_moves.tick_children(self)?;
 			self.moves = taken_moves;
 		}
+		self.record_probes();
+		self.ticks += 1;
 		Ok(())
 	}
+	/// records the main world's probes from the drawmap, starting a new trace if the probes changed
+	fn record_probes(&mut self) {
+		let main = match self.worlds.at(self.main_id) {
+			Some(main) if !main.probes().is_empty() => main,
+			_ => {
+				self.trace = None;
+				return;
+			}
+		};
+		let trace = match &mut self.trace {
+			Some(trace) if trace.matches(self.main_id, main) => trace,
+			trace => trace.insert(Trace::new(self.main_id, main, self.ticks)),
+		};
+		trace.record(|(x, y)| {
+			self.drawmap
+				.at(x, y)
+				.map(|dt| *dt != DrawType::Off)
+				.unwrap_or(false)
+		});
+	}
 
 	/// creates a new world, returning its id
 	pub fn push(&mut self) -> WorldId {
@@ -81,6 +149,8 @@ impl Game {
 	}
 
 	pub fn switch_main(&mut self, id: WorldId) {
+		// an edit left open would end up recorded as an edit of the new main world
+		self.finish_edit();
 		let prev_id = self.main_id;
 		self.main_id = id;
 		if let Err(err) = self.regenerate_moves(prev_id) {
@@ -95,6 +165,9 @@ impl Game {
 	pub fn regenerate_moves(&mut self, prev_id: WorldId) -> anyhow::Result<()> {
 		self.moves = IngameWorld::generate(self, self.main_id)
 			.with_context(|| "IngameWorld::generate failed in Game::regenerate_moves")?;
+		// the new moves have nothing to do with the ticks before them
+		self.oscillation.reset();
+		self.rewind.clear();
 		self.generate_program_for(prev_id)
 			.with_context(|| "generate_program_for failed in Game::regenerate_moves")?;
 		Ok(())
@@ -119,6 +192,17 @@ impl Game {
 		}
 		Ok(())
 	}
+	/// regenerates every program with the new timing, along with the moves so every instance uses them
+	pub fn set_timing(&mut self, timing: processor::Timing) -> anyhow::Result<()> {
+		self.timing = timing;
+		let wids = self.worlds.iter().map(|(wid, _)| *wid).collect::<Vec<_>>();
+		for wid in wids {
+			self.generate_program_for(wid)
+				.with_context(|| "in Game::set_timing")?;
+		}
+		self.regenerate_moves(self.main_id)
+			.with_context(|| "in Game::set_timing")
+	}
 	pub fn generate_program_for(&mut self, wid: WorldId) -> anyhow::Result<()> {
 		let program = match processor::world_to_instructions(self, wid) {
 			Ok(a) => Some(a),
@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+
+use crate::{
+	drawtype::DrawType,
+	game::{Game, IngameWorld},
+	world::World,
+};
+
+/// what the game was like right before a tick, everything ticking changes
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Snapshot {
+	moves: IngameWorld,
+	ticks: u64,
+	drawmap: World<DrawType>,
+}
+
+/// the last few ticks of the main world, so they can be stepped back through \
+/// keeps nothing unless it's made with [Rewind::new] and paused (see [Rewind::set_paused]),
+/// cloning every instance every tick isn't free so a running game doesn't do it
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Rewind {
+	/// oldest first
+	snapshots: VecDeque<Snapshot>,
+	capacity: usize,
+	paused: bool,
+}
+impl Rewind {
+	/// keeps the last `capacity` ticks stepped through while paused
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			snapshots: VecDeque::with_capacity(capacity),
+			capacity,
+			paused: false,
+		}
+	}
+	/// only ticks taken while paused can be stepped back, resuming forgets them
+	pub fn set_paused(&mut self, paused: bool) {
+		self.paused = paused;
+		if !paused {
+			self.clear();
+		}
+	}
+	/// how many ticks can be stepped back
+	pub fn len(&self) -> usize {
+		self.snapshots.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.snapshots.is_empty()
+	}
+	pub fn clear(&mut self) {
+		self.snapshots.clear();
+	}
+	fn push(&mut self, snapshot: Snapshot) {
+		if self.snapshots.len() == self.capacity {
+			self.snapshots.pop_front();
+		}
+		self.snapshots.push_back(snapshot);
+	}
+}
+
+impl Game {
+	/// saves the game as it is before ticking, see [Rewind]
+	pub(super) fn snapshot(&mut self) {
+		if self.rewind.capacity == 0 || !self.rewind.paused {
+			return;
+		}
+		let snapshot = Snapshot {
+			moves: self.moves.clone(),
+			ticks: self.ticks,
+			drawmap: self.drawmap.clone(),
+		};
+		self.rewind.push(snapshot);
+	}
+	/// undoes the last tick, returns false if there's no tick left to go back to \
+	/// ticking again afterwards does the same tick over, unless something got changed in the meantime
+	pub fn step_back(&mut self) -> bool {
+		let Snapshot {
+			moves,
+			ticks,
+			drawmap,
+		} = match self.rewind.snapshots.pop_back() {
+			Some(snapshot) => snapshot,
+			None => return false,
+		};
+		self.moves = moves;
+		self.ticks = ticks;
+		self.drawmap = drawmap;
+
+		// the tick we went back to gets recorded again when it's redone
+		match &mut self.trace {
+			Some(trace) if trace.start() <= ticks => trace.truncate(ticks),
+			trace => *trace = None,
+		}
+		self.oscillation.reset();
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::world::{Block, Direction};
+
+	use super::*;
+
+	#[test]
+	fn stepping_back_and_ticking_again_gets_to_the_same_tick() {
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Not(false);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Bottom);
+		*world.mut_at(1, 1) = Block::Wire(Direction::Left);
+		*world.mut_at(0, 1) = Block::Wire(Direction::Top);
+		world.set_probe(0, 0, "not".to_string());
+		game.main_id = wid;
+		game.regenerate_moves(wid).unwrap();
+		game.rewind = Rewind::new(5);
+
+		// a running game doesn't keep anything
+		for _ in 0..3 {
+			game.tick().unwrap();
+		}
+		assert!(game.rewind.is_empty());
+
+		game.rewind.set_paused(true);
+		for _ in 0..7 {
+			game.tick().unwrap();
+		}
+		let ticked = game.clone();
+		assert_eq!(game.rewind.len(), 5);
+
+		for _ in 0..3 {
+			assert!(game.step_back());
+		}
+		assert_eq!(game.ticks, 7);
+		assert_eq!(game.trace.as_ref().unwrap().end(), 7);
+		for _ in 0..3 {
+			game.tick().unwrap();
+		}
+		assert_eq!(game.moves, ticked.moves);
+		assert_eq!(game.drawmap, ticked.drawmap);
+		assert_eq!(game.trace, ticked.trace);
+
+		while game.step_back() {}
+		assert_eq!(game.ticks, 5);
+	}
+}
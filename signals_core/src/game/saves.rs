@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+
+use crate::world::Pins;
+
+use super::{WorldId, Worlds};
+
+// everything that isn't part of the worlds themselves gets written after them, in this order
+// saves from before any of these existed just end early, which is read as them being empty
+
+/// annotations of every world that has any
+type SavedAnnotations = HashMap<WorldId, HashMap<(i32, i32), String>>;
+/// pin info of every world that has any
+type SavedPins = HashMap<WorldId, Pins>;
+/// probe names of every world that has any
+type SavedProbes = HashMap<WorldId, HashMap<(i32, i32), String>>;
+
+pub fn read_worlds(path: &str) -> anyhow::Result<Worlds> {
+	load_worlds(
+		&std::fs::read(path)
+			.with_context(|| format!("couldn't read {path} in saves::read_worlds"))?,
+	)
+}
+pub fn load_worlds(bytes: &[u8]) -> anyhow::Result<Worlds> {
+	let decomp_bytes = match decompress_to_vec_with_limit(bytes, 60000) {
+		Ok(a) => a,
+		Err(err) => return Err(anyhow!("failed to decompress save bytes:\n{err}")),
+	};
+	let mut rest = decomp_bytes.as_slice();
+	let mut worlds: Worlds = bincode::deserialize_from(&mut rest)?;
+
+	if !rest.is_empty() {
+		let annotations: SavedAnnotations = bincode::deserialize_from(&mut rest)
+			.with_context(|| "failed to load annotations from save")?;
+		for (wid, annotations) in annotations {
+			let world = match worlds.at_mut(wid) {
+				Some(a) => a,
+				None => {
+					eprintln!("save has annotations for world {wid:?}, which doesn't exist");
+					continue;
+				}
+			};
+			for ((x, y), text) in annotations {
+				world.set_annotation(x, y, text);
+			}
+		}
+	}
+	if !rest.is_empty() {
+		let pins: SavedPins = bincode::deserialize_from(&mut rest)
+			.with_context(|| "failed to load pin info from save")?;
+		for (wid, pins) in pins {
+			match worlds.at_mut(wid) {
+				Some(world) => *world.pins_mut() = pins,
+				None => eprintln!("save has pin info for world {wid:?}, which doesn't exist"),
+			}
+		}
+	}
+	if !rest.is_empty() {
+		let probes: SavedProbes =
+			bincode::deserialize(rest).with_context(|| "failed to load probes from save")?;
+		for (wid, probes) in probes {
+			let world = match worlds.at_mut(wid) {
+				Some(a) => a,
+				None => {
+					eprintln!("save has probes for world {wid:?}, which doesn't exist");
+					continue;
+				}
+			};
+			for ((x, y), name) in probes {
+				world.set_probe(x, y, name);
+			}
+		}
+	}
+
+	Ok(worlds)
+}
+pub fn write_worlds(worlds: &Worlds) -> anyhow::Result<Vec<u8>> {
+	let mut raw_bin = bincode::serialize(worlds)?;
+
+	let annotations: SavedAnnotations = worlds
+		.iter()
+		.map(|(wid, world)| {
+			let annotations = world
+				.annotations()
+				.map(|(coords, text)| (coords, text.to_string()))
+				.collect::<HashMap<_, _>>();
+			(*wid, annotations)
+		})
+		.filter(|(_, annotations)| !annotations.is_empty())
+		.collect();
+	let pins: SavedPins = worlds
+		.iter()
+		.filter(|(_, world)| !world.pins().is_empty())
+		.map(|(wid, world)| (*wid, world.pins().clone()))
+		.collect();
+	let probes: SavedProbes = worlds
+		.iter()
+		.map(|(wid, world)| {
+			let probes = world
+				.probes()
+				.into_iter()
+				.map(|(coords, name)| (coords, name.to_string()))
+				.collect::<HashMap<_, _>>();
+			(*wid, probes)
+		})
+		.filter(|(_, probes)| !probes.is_empty())
+		.collect();
+
+	// everything before the last thing that's written has to be written too, even if it's empty
+	if !annotations.is_empty() || !pins.is_empty() || !probes.is_empty() {
+		raw_bin.extend(bincode::serialize(&annotations)?);
+	}
+	if !pins.is_empty() || !probes.is_empty() {
+		raw_bin.extend(bincode::serialize(&pins)?);
+	}
+	if !probes.is_empty() {
+		raw_bin.extend(bincode::serialize(&probes)?);
+	}
+
+	let comp_bin = compress_to_vec(&raw_bin, 6);
+	Ok(comp_bin)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::world::{Block, PinInfo, PinKind, World};
+
+	#[test]
+	fn annotations_survive_saving() {
+		let mut world = World::default();
+		*world.mut_at(3, 4) = Block::Router;
+		world.set_annotation(3, 4, "carry in".to_string());
+		let mut worlds = Worlds::default();
+		let wid = worlds.push(world);
+
+		let loaded = load_worlds(&write_worlds(&worlds).unwrap()).unwrap();
+		assert_eq!(loaded, worlds);
+		assert_eq!(loaded.at(wid).unwrap().annotation(3, 4), Some("carry in"));
+	}
+
+	#[test]
+	fn pin_info_survives_saving() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Output(0);
+		world.pins_mut().set(
+			PinKind::Output,
+			0,
+			PinInfo {
+				name: "sum".to_string(),
+				color: Some((255, 0, 0)),
+				description: None,
+			},
+		);
+		let mut worlds = Worlds::default();
+		worlds.push(world);
+
+		let loaded = load_worlds(&write_worlds(&worlds).unwrap()).unwrap();
+		assert_eq!(loaded, worlds);
+	}
+
+	#[test]
+	fn probes_survive_saving() {
+		let mut world = World::default();
+		*world.mut_at(2, 2) = Block::Not(false);
+		world.set_probe(2, 2, "clk".to_string());
+		let mut worlds = Worlds::default();
+		let wid = worlds.push(world);
+
+		let loaded = load_worlds(&write_worlds(&worlds).unwrap()).unwrap();
+		assert_eq!(loaded.at(wid).unwrap().probe(2, 2), Some("clk"));
+	}
+
+	#[test]
+	fn saves_without_annotations_still_load() {
+		let mut worlds = Worlds::default();
+		worlds.push(World::default());
+
+		// what saves looked like before annotations
+		let old_save = compress_to_vec(&bincode::serialize(&worlds).unwrap(), 6);
+		assert_eq!(load_worlds(&old_save).unwrap(), worlds);
+	}
+}
@@ -65,7 +65,8 @@ use std::borrow::Cow;
 /// the worlds themselves are in [Worlds]
 pub struct WorldsTree {
 	pub name: Cow<'static, str>,
-	pub color: Option<sui::Color>,
+	/// (r, g, b)
+	pub color: Option<(u8, u8, u8)>,
 
 	pub categories: Vec<WorldsTree>,
 	pub worlds: Vec<WorldId>,
@@ -0,0 +1,12 @@
+//! everything signals needs to load and simulate worlds, without any graphics \
+//! the game itself (the `signals` crate) is built on top of this
+
+pub mod drawtype;
+pub mod game;
+pub mod oscillation;
+pub mod processor;
+pub mod trace;
+pub mod world;
+
+mod simulator;
+pub use simulator::Simulator;
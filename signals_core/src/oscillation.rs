@@ -0,0 +1,248 @@
+//! noticing when a world stops changing or starts repeating itself, and which blocks keep it going \
+//! a tick is fully decided by the block states and the moves going into it, so once those are the same
+//! as they were some ticks ago, everything in between is going to happen again forever
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::world::Move;
+
+/// how many ticks back a repeat is looked for, longer periods go unnoticed
+pub const HISTORY: usize = 256;
+
+/// (from, to) of a signal passed inside the world
+type Edge = ((i32, i32), (i32, i32));
+/// every signal passed inside the world in one tick
+type Edges = Vec<Edge>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum Activity {
+	/// nothing repeated in the last [HISTORY] ticks
+	#[default]
+	Changing,
+	/// no signals are moving anymore
+	Settled,
+	/// the same `period` ticks keep happening, a period of 1 is a fixed point that still has signals moving \
+	/// `looping` are the blocks in loops that power themselves, like two routers next to each other
+	/// or a not whose output gets back to it
+	Repeating {
+		period: usize,
+		looping: HashSet<(i32, i32)>,
+	},
+}
+
+impl std::fmt::Display for Activity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Activity::Changing => write!(f, "changing"),
+			Activity::Settled => write!(f, "settled"),
+			Activity::Repeating { period, looping } if looping.is_empty() => {
+				write!(f, "repeating every {period} ticks")
+			}
+			Activity::Repeating { period, looping } => write!(
+				f,
+				"repeating every {period} ticks, {} blocks in loops",
+				looping.len()
+			),
+		}
+	}
+}
+
+/// keeps the last [HISTORY] ticks of a world around to find out its [Activity]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct OscillationDetector {
+	/// (hash of everything the tick depends on, signals passed inside the world), newest last
+	history: VecDeque<(u64, Edges)>,
+	activity: Activity,
+}
+impl OscillationDetector {
+	/// call before every tick with a hash of the block states and the moves about to be ticked \
+	/// anything else the tick depends on (like the world's blocks) has to be in the hash too
+	pub fn observe(&mut self, hash: u64, moves: &[Move]) -> &Activity {
+		let period = self
+			.history
+			.iter()
+			.rev()
+			.position(|(h, _)| *h == hash)
+			.map(|i| i + 1);
+
+		self.history.push_back((hash, edges(moves)));
+		if self.history.len() > HISTORY {
+			self.history.pop_front();
+		}
+
+		self.activity = match (period, &self.activity) {
+			(None, _) => Activity::Changing,
+			(Some(1), _) if moves.is_empty() => Activity::Settled,
+			// still the same cycle, no need to look for the loops again
+			(Some(period), Activity::Repeating { period: prev, .. }) if period == *prev => {
+				return &self.activity
+			}
+			(Some(period), _) => {
+				let edges = self
+					.history
+					.iter()
+					.rev()
+					.take(period)
+					.flat_map(|(_, edges)| edges.iter().copied())
+					.collect::<HashSet<_>>();
+				Activity::Repeating {
+					period,
+					looping: looping(&edges),
+				}
+			}
+		};
+		&self.activity
+	}
+	pub fn activity(&self) -> &Activity {
+		&self.activity
+	}
+	/// forgets every tick, for when the world gets swapped for another one
+	pub fn reset(&mut self) {
+		*self = Self::default();
+	}
+}
+
+/// (from, to) of every signal a block passed to its neighbour \
+/// signals from outside the world and wireless ones don't say where they came from, so they're left out
+fn edges(moves: &[Move]) -> Edges {
+	moves
+		.iter()
+		.filter_map(|mov| match mov {
+			Move::Inside {
+				to,
+				from: Some(from),
+				..
+			} => {
+				let (rx, ry) = from.rel();
+				Some(((to.0 + rx, to.1 + ry), *to))
+			}
+			_ => None,
+		})
+		.collect()
+}
+
+/// every block that can reach itself through the edges \
+/// kosaraju's, with the recursion unrolled since loops can get long
+fn looping(edges: &HashSet<Edge>) -> HashSet<(i32, i32)> {
+	let mut forward = HashMap::<(i32, i32), Vec<(i32, i32)>>::new();
+	let mut backward = HashMap::<(i32, i32), Vec<(i32, i32)>>::new();
+	for &(from, to) in edges {
+		forward.entry(from).or_default().push(to);
+		backward.entry(to).or_default().push(from);
+	}
+
+	// blocks in the order their search finished
+	let mut finished = Vec::new();
+	let mut seen = HashSet::new();
+	for &start in forward.keys() {
+		if !seen.insert(start) {
+			continue;
+		}
+		let mut stack = vec![(start, 0)];
+		while let Some((block, next)) = stack.pop() {
+			match forward
+				.get(&block)
+				.and_then(|neighbours| neighbours.get(next))
+			{
+				Some(&neighbour) => {
+					stack.push((block, next + 1));
+					if seen.insert(neighbour) {
+						stack.push((neighbour, 0));
+					}
+				}
+				None => finished.push(block),
+			}
+		}
+	}
+
+	// going backwards from the last finished block only reaches the blocks in its loop
+	let mut looping = HashSet::new();
+	let mut assigned = HashSet::new();
+	for &start in finished.iter().rev() {
+		if !assigned.insert(start) {
+			continue;
+		}
+		let mut component = vec![start];
+		let mut stack = vec![start];
+		while let Some(block) = stack.pop() {
+			for &neighbour in backward.get(&block).into_iter().flatten() {
+				if assigned.insert(neighbour) {
+					component.push(neighbour);
+					stack.push(neighbour);
+				}
+			}
+		}
+		// signals never go from a block to itself, so a loop is always more than one block
+		if component.len() > 1 {
+			looping.extend(component);
+		}
+	}
+	looping
+}
+
+#[cfg(test)]
+mod tests {
+	use std::hash::{DefaultHasher, Hash, Hasher};
+
+	use super::*;
+	use crate::world::{Block, BlockStates, Direction, Signal, World};
+
+	/// ticks the world until it's been repeating for a while, returning what it's doing
+	fn activity_of(world: &World, mut moves: Vec<Move>) -> Activity {
+		let mut state = BlockStates::default();
+		let mut detector = OscillationDetector::default();
+		for _ in 0..50 {
+			let mut hasher = DefaultHasher::new();
+			state.hash(&mut hasher);
+			moves.hash(&mut hasher);
+			detector.observe(hasher.finish(), &moves);
+			moves = world.tick(&mut state, moves, |_, _, _| {});
+		}
+		detector.activity().clone()
+	}
+
+	#[test]
+	fn wires_settle() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Right);
+		let moves = vec![Move::new((0, 0), Some(Direction::Left), Signal::Default)];
+		assert_eq!(activity_of(&world, moves), Activity::Settled);
+	}
+
+	#[test]
+	fn routers_power_each_other() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Router;
+		*world.mut_at(1, 0) = Block::Router;
+		*world.mut_at(2, 0) = Block::Wire(Direction::Right);
+		// the signal bounces between them, so it's on one router one tick and on the other the next
+		let moves = vec![Move::new((0, 0), Some(Direction::Left), Signal::Default)];
+		assert_eq!(
+			activity_of(&world, moves),
+			Activity::Repeating {
+				period: 2,
+				looping: HashSet::from([(0, 0), (1, 0)]),
+			}
+		);
+	}
+
+	#[test]
+	fn not_loop_oscillates() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Not(false);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Bottom);
+		*world.mut_at(1, 1) = Block::Wire(Direction::Left);
+		*world.mut_at(0, 1) = Block::Wire(Direction::Top);
+		// a not on its own never stops powering its neighbours, but that's not a loop
+		*world.mut_at(5, 5) = Block::Not(false);
+
+		match activity_of(&world, vec![]) {
+			Activity::Repeating { period, looping } => {
+				assert!(period > 1);
+				assert_eq!(looping, HashSet::from([(0, 0), (1, 0), (1, 1), (0, 1)]));
+			}
+			activity => panic!("expected the not to oscillate, got {activity:?}"),
+		}
+	}
+}
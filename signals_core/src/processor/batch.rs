@@ -0,0 +1,270 @@
+//! running a program for 64 sets of inputs at once \
+//! every bit of [BatchMemory] is a u64 with one lane per execution, so a single or, and, xor or not does all of
+//! them in one go. used for truth tables and for processors of the same world ticking side by side
+
+use anyhow::anyhow;
+
+use super::{footprint, Compiled, Instruction};
+
+/// how many executions fit in one [BatchMemory]
+pub const LANES: usize = 64;
+/// a truth table has 2^inputs rows, more inputs than this is too many to go through
+pub const MAX_TABLE_INPUTS: usize = 24;
+
+/// [super::Memory], but with [LANES] executions side by side \
+/// lane n of every bit belongs to the nth execution, lanes never affect each other
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchMemory {
+	mem: Vec<u64>,
+	/// the same for every lane, used by [Instruction::SummonClock]
+	tick: u64,
+	/// one u64 per state bit, in lanes like the memory, see [Instruction::SummonState]
+	state: Vec<u64>,
+}
+impl BatchMemory {
+	pub fn new(bits: usize) -> Self {
+		Self {
+			mem: vec![0; bits],
+			..Default::default()
+		}
+	}
+	pub fn bits(&self) -> usize {
+		self.mem.len()
+	}
+	/// every lane of bit `i`
+	pub fn get(&self, i: usize) -> u64 {
+		self.mem[i]
+	}
+	pub fn lane(&self, i: usize, lane: usize) -> bool {
+		self.mem[i] >> lane & 1 == 1
+	}
+	pub fn set_tick(&mut self, tick: u64) {
+		self.tick = tick;
+	}
+	/// the state bits the next execution starts with, missing ones are false in every lane
+	pub fn set_state(&mut self, state: Vec<u64>) {
+		self.state = state;
+	}
+	pub fn take_state(&mut self) -> Vec<u64> {
+		std::mem::take(&mut self.state)
+	}
+
+	/// `inputs` has one u64 per input, lane n being what the nth execution gets \
+	/// errors without running anything if the instructions point past the end of the memory
+	pub fn execute(&mut self, instructions: &[Instruction], inputs: &[u64]) -> anyhow::Result<()> {
+		let needed = footprint(instructions);
+		if needed > self.bits() {
+			return Err(anyhow!(
+				"the instructions use {needed} bits of memory, but there's only {}",
+				self.bits()
+			));
+		}
+
+		let mem = &mut self.mem;
+		for inst in instructions {
+			match *inst {
+				Instruction::SummonInput { id, out } => {
+					mem[out] = inputs.get(id).copied().unwrap_or_else(|| {
+						eprintln!("program tried to access an input that doesn't exist");
+						0
+					})
+				}
+				Instruction::SummonClock { period, phase, out } => {
+					let period = period.max(1) as u64;
+					mem[out] = spread((self.tick + phase as u64).is_multiple_of(period))
+				}
+				Instruction::SummonState { id, out } => {
+					mem[out] = self.state.get(id).copied().unwrap_or(0)
+				}
+				Instruction::StoreState { ptr, id } => {
+					if self.state.len() <= id {
+						self.state.resize(id + 1, 0);
+					}
+					self.state[id] = mem[ptr];
+				}
+
+				Instruction::Not { ptr, out } => mem[out] = !mem[ptr],
+				Instruction::Or { a, b, out } => mem[out] = mem[a] | mem[b],
+				Instruction::Set { ptr, val } => mem[ptr] = spread(val),
+				Instruction::Copy { src_ptr, dst_ptr } => mem[dst_ptr] = mem[src_ptr],
+
+				Instruction::And { a, b, out } => mem[out] = mem[a] & mem[b],
+				Instruction::Xor { a, b, out } => mem[out] = mem[a] ^ mem[b],
+			}
+		}
+		Ok(())
+	}
+}
+
+/// the same value in every lane
+fn spread(val: bool) -> u64 {
+	if val {
+		u64::MAX
+	} else {
+		0
+	}
+}
+
+/// one bool per lane, the first one going in lane 0. only the first [LANES] are used
+pub fn pack(lanes: impl IntoIterator<Item = bool>) -> u64 {
+	lanes
+		.into_iter()
+		.take(LANES)
+		.enumerate()
+		.fold(0, |packed, (lane, val)| packed | (val as u64) << lane)
+}
+
+/// calls `row` with the outputs of a program for every combination of its inputs, in order, input n being bit n
+/// of the row's index \
+/// rows are handed over one at a time instead of all being collected, a table can have 2^[MAX_TABLE_INPUTS] of them \
+/// state bits and clocks are what they'd be on the first tick
+pub fn truth_table(
+	compiled: &Compiled,
+	inputs_len: usize,
+	outputs_len: usize,
+	mut row: impl FnMut(usize, &[bool]),
+) -> anyhow::Result<()> {
+	if inputs_len > MAX_TABLE_INPUTS {
+		return Err(anyhow!(
+			"{inputs_len} inputs is too many for a truth table, it can have at most {MAX_TABLE_INPUTS}"
+		));
+	}
+
+	let rows = 1_usize << inputs_len;
+	let mut outputs = vec![false; outputs_len];
+	for first in (0..rows).step_by(LANES) {
+		let lanes = LANES.min(rows - first);
+		let inputs = (0..inputs_len)
+			.map(|id| pack((first..first + lanes).map(|row| row >> id & 1 == 1)))
+			.collect::<Vec<_>>();
+
+		let mut memory = BatchMemory::new(compiled.bits);
+		memory.execute(&compiled.insts, &inputs)?;
+		for lane in 0..lanes {
+			for (id, val) in outputs.iter_mut().enumerate() {
+				*val = memory.lane(id, lane);
+			}
+			row(first + lane, &outputs);
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::processor::Memory;
+
+	/// every instruction there is, over 7 inputs and 2 state bits
+	fn everything() -> Vec<Instruction> {
+		vec![
+			Instruction::SummonInput { id: 0, out: 0 },
+			Instruction::SummonInput { id: 1, out: 5 },
+			Instruction::Xor { a: 0, b: 5, out: 0 },
+			Instruction::SummonInput { id: 2, out: 6 },
+			Instruction::SummonState { id: 0, out: 7 },
+			Instruction::And { a: 6, b: 7, out: 1 },
+			Instruction::SummonInput { id: 3, out: 6 },
+			Instruction::SummonInput { id: 4, out: 7 },
+			Instruction::Or { a: 6, b: 7, out: 6 },
+			Instruction::Not { ptr: 6, out: 2 },
+			Instruction::SummonClock {
+				period: 3,
+				phase: 1,
+				out: 7,
+			},
+			Instruction::SummonInput { id: 5, out: 8 },
+			Instruction::Or { a: 7, b: 8, out: 3 },
+			Instruction::Set { ptr: 8, val: true },
+			Instruction::SummonInput { id: 6, out: 9 },
+			Instruction::Xor { a: 8, b: 9, out: 9 },
+			Instruction::SummonState { id: 1, out: 8 },
+			Instruction::And { a: 8, b: 9, out: 4 },
+			Instruction::Copy {
+				src_ptr: 0,
+				dst_ptr: 8,
+			},
+			Instruction::StoreState { ptr: 8, id: 0 },
+			Instruction::StoreState { ptr: 2, id: 1 },
+		]
+	}
+
+	#[test]
+	fn same_as_memory() {
+		let insts = everything();
+		let bits = footprint(&insts);
+		// 7 inputs and 2 state bits, two batches' worth
+		let rows = (0..1 << 9).collect::<Vec<usize>>();
+
+		for tick in 0..3 {
+			for batch in rows.chunks(LANES) {
+				let inputs = (0..7)
+					.map(|id| pack(batch.iter().map(|row| row >> id & 1 == 1)))
+					.collect::<Vec<_>>();
+				let state = (7..9)
+					.map(|bit| pack(batch.iter().map(|row| row >> bit & 1 == 1)))
+					.collect::<Vec<_>>();
+
+				let mut batched = BatchMemory::new(bits);
+				batched.set_tick(tick);
+				batched.set_state(state);
+				batched.execute(&insts, &inputs).unwrap();
+				let batched_state = batched.take_state();
+
+				for (lane, row) in batch.iter().enumerate() {
+					let mut mem = Memory::new(bits);
+					mem.set_tick(tick);
+					mem.set_state(vec![row >> 7 & 1 == 1, row >> 8 & 1 == 1]);
+					let inputs = (0..7).map(|id| row >> id & 1 == 1).collect::<Vec<_>>();
+					mem.execute(&insts, &inputs).unwrap();
+
+					for i in 0..bits {
+						assert_eq!(batched.lane(i, lane), mem.get(i), "bit {i} of row {row}");
+					}
+					let state = batched_state
+						.iter()
+						.map(|bits| bits >> lane & 1 == 1)
+						.collect::<Vec<_>>();
+					assert_eq!(state, mem.take_state());
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn full_adder_table() {
+		// 0: sum, 1: carry
+		let insts = vec![
+			Instruction::SummonInput { id: 0, out: 2 },
+			Instruction::SummonInput { id: 1, out: 3 },
+			Instruction::Xor { a: 2, b: 3, out: 0 },
+			Instruction::And { a: 2, b: 3, out: 1 },
+			Instruction::SummonInput { id: 2, out: 2 },
+			Instruction::And { a: 0, b: 2, out: 3 },
+			Instruction::Or { a: 1, b: 3, out: 1 },
+			Instruction::Xor { a: 0, b: 2, out: 0 },
+		];
+		let compiled = Compiled {
+			bits: footprint(&insts),
+			insts,
+			changes_by_itself: false,
+			clock_period: 1,
+		};
+
+		let mut table = vec![];
+		truth_table(&compiled, 3, 2, |row, outputs| {
+			assert_eq!(row, table.len());
+			table.push(outputs.to_vec());
+		})
+		.unwrap();
+		let expected = (0..8_usize)
+			.map(|row| {
+				let ones = row.count_ones();
+				vec![ones % 2 == 1, ones >= 2]
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(table, expected);
+
+		assert!(truth_table(&compiled, MAX_TABLE_INPUTS + 1, 2, |_, _| {}).is_err());
+	}
+}
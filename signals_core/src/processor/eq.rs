@@ -4,7 +4,7 @@ use crate::{game::WorldId, processor::Instruction};
 
 use anyhow::{anyhow, Context};
 
-use super::stack::Stack;
+use super::{stack::Stack, store::Store};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ForeignRef {
@@ -18,6 +18,16 @@ pub enum Equation {
 	Or(Box<Equation>, Box<Equation>),
 	Not(Box<Equation>),
 	Const(bool),
+	/// true every `period` ticks, see [crate::world::Block::Clock] \
+	/// clocks are treated as external inputs, their value comes from the tick the program runs at
+	Clock {
+		period: u32,
+		phase: u32,
+	},
+	/// the value a state bit had after the previous execution, false on the first one \
+	/// state bits break up feedback loops and hold what latches and flip-flops store, see
+	/// [crate::processor::world_to_instructions::Sequential]
+	State(usize),
 
 	/// Foreign is special, as it can't be turned into instructions as is. \
 	/// you need to convert it to a plain equation one way or another
@@ -68,7 +78,7 @@ impl Equation {
 						.map(|in_eq| internal(in_eq, f.clone()))
 						.collect::<Result<Vec<_>, _>>()?,
 				)),
-				Equation::Const(_) => Ok(eq),
+				Equation::Const(_) | Equation::Clock { .. } | Equation::State(_) => Ok(eq),
 				Equation::Shared(sh) => sh.store.clone().with_mut_borrow(|data| {
 					let replacer_eq: Equation = unsafe { std::mem::zeroed() };
 					let data_eq = std::mem::replace(&mut data.eq, replacer_eq);
@@ -90,7 +100,10 @@ impl Equation {
 			f: Rc<F>,
 		) -> Result<Equation, E> {
 			match eq {
-				Equation::Input(_) | Equation::Const(_) => Ok(eq),
+				Equation::Input(_)
+				| Equation::Const(_)
+				| Equation::Clock { .. }
+				| Equation::State(_) => Ok(eq),
 				Equation::Or(a_eq, b_eq) => Ok(Equation::or(
 					internal(*a_eq, f.clone())?,
 					internal(*b_eq, f.clone())?,
@@ -114,12 +127,37 @@ impl Equation {
 		}
 		internal(self, Rc::new(f))
 	}
+	/// replaces every [Equation::State], for renumbering the state bits of an inlined foreign
+	pub fn map_states(self, f: &impl Fn(usize) -> Self) -> Self {
+		match self {
+			Equation::State(id) => f(id),
+			Equation::Input(_) | Equation::Const(_) | Equation::Clock { .. } => self,
+			Equation::Or(a_eq, b_eq) => Equation::or(a_eq.map_states(f), b_eq.map_states(f)),
+			Equation::Not(n_eq) => Equation::not(n_eq.map_states(f)),
+			Equation::Foreign(w_id, inst_id, id, in_eqs) => Equation::Foreign(
+				w_id,
+				inst_id,
+				id,
+				in_eqs
+					.into_iter()
+					.map(|in_eq| in_eq.map_states(f))
+					.collect(),
+			),
+			Equation::Shared(sh) => sh.store.clone().with_mut_borrow(|data| {
+				let replacer_eq: Equation = unsafe { std::mem::zeroed() };
+				let data_eq = std::mem::replace(&mut data.eq, replacer_eq);
+				data.eq = data_eq.map_states(f);
+
+				Equation::Shared(sh)
+			}),
+		}
+	}
 
 	pub fn complexity(&self) -> i32 {
 		fn internal(eq: &Equation, map: &mut Vec<u64>) -> i32 {
 			match eq {
 				&Equation::Const(_) => 0,
-				&Equation::Input(_) => 1,
+				&Equation::Input(_) | &Equation::Clock { .. } | &Equation::State(_) => 1,
 				Equation::Not(n_eq) => {
 					if let Some((a, b)) = eq.xor_recognition() {
 						internal(&a, map) + internal(&b, map) + 1
@@ -162,6 +200,9 @@ impl Equation {
 	pub fn simplify(self) -> Self {
 		match self {
 			Self::Input(id) => Self::Input(id),
+			Self::Clock { period: 0 | 1, .. } => Self::Const(true),
+			Self::Clock { period, phase } => Self::Clock { period, phase },
+			Self::State(id) => Self::State(id),
 			Self::Not(n_eq) => {
 				let n_eq = *n_eq;
 				let n_eq = n_eq.simplify();
@@ -225,6 +266,12 @@ impl Equation {
 	) -> anyhow::Result<()> {
 		match self {
 			&Equation::Input(id) => insts.push(Instruction::SummonInput { id, out: out_ptr }),
+			&Equation::Clock { period, phase } => insts.push(Instruction::SummonClock {
+				period,
+				phase,
+				out: out_ptr,
+			}),
+			&Equation::State(id) => insts.push(Instruction::SummonState { id, out: out_ptr }),
 			Equation::Not(n_eq) => {
 				macro_rules! base_case {
 					() => {{
@@ -289,7 +336,10 @@ impl Equation {
 
 	pub fn reset_shareds(&self) {
 		match self {
-			Equation::Const(_) | Equation::Input(_) => (),
+			Equation::Const(_)
+			| Equation::Input(_)
+			| Equation::Clock { .. }
+			| Equation::State(_) => (),
 			Equation::Not(n_eq) => n_eq.reset_shareds(),
 			Equation::Or(a_eq, b_eq) => {
 				a_eq.reset_shareds();
@@ -307,7 +357,10 @@ impl Equation {
 	}
 	pub fn reservations_internal(eq: &Equation, map: &mut Vec<u64>) -> usize {
 		match eq {
-			Equation::Const(_) | Equation::Input(_) => 0,
+			Equation::Const(_)
+			| Equation::Input(_)
+			| Equation::Clock { .. }
+			| Equation::State(_) => 0,
 			Equation::Not(n_eq) => Self::reservations_internal(n_eq.as_ref(), map),
 			Equation::Or(a_eq, b_eq) => {
 				Self::reservations_internal(a_eq.as_ref(), map)
@@ -445,7 +498,7 @@ impl Equation {
 /// this data structure is unique to every instance of [SharedData]
 /// just contains a fake mutable reference to [SharedData]
 pub struct SharedStore {
-	pub store: sui::core::Store<SharedData>,
+	pub store: Store<SharedData>,
 }
 impl std::hash::Hash for SharedStore {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
@@ -457,7 +510,7 @@ impl std::hash::Hash for SharedStore {
 impl SharedStore {
 	pub fn new(eq: Equation) -> Self {
 		Self {
-			store: sui::core::Store::new(SharedData::new(eq)),
+			store: Store::new(SharedData::new(eq)),
 		}
 	}
 
@@ -542,12 +595,12 @@ mod tests {
 		eq2.to_insts(1, stack.clone(), &mut insts).expect("hey 2");
 
 		use crate::processor::Memory;
-		let mut mem = Memory::default();
+		let mut mem = Memory::new(crate::processor::footprint(&insts));
 		for zero in [false, true] {
 			for one in [false, true] {
 				for two in [false, true] {
 					for three in [false, true] {
-						mem.execute(&insts, &[zero, one, two, three]);
+						mem.execute(&insts, &[zero, one, two, three]).unwrap();
 						dbg!(zero, one, two, three);
 						assert_eq!(mem.get(0), (zero && two) && (one && three));
 						println!("shared && shared worked");
@@ -0,0 +1,357 @@
+pub mod world_to_instructions;
+use std::ops::{Index, Range};
+
+use anyhow::anyhow;
+
+pub use world_to_instructions::world_to_instructions;
+
+pub mod batch;
+pub mod eq;
+pub mod optimize;
+pub mod program;
+pub mod stack;
+pub mod store;
+pub mod tick_accurate;
+
+/// how closely a program sticks to the timing of simulating its world, see [crate::game::Game::set_timing]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Timing {
+	/// outputs come out the same tick the inputs go in, however far apart they are
+	#[default]
+	Instant,
+	/// every signal takes as many ticks as it would when simulated, see [tick_accurate]
+	TickAccurate,
+}
+
+/// a processor can't have more bits of memory than this, see [world_to_instructions]
+pub const MAX_BITS: usize = 1 << 16;
+
+/// a world turned into instructions, along with how much memory running them takes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Compiled {
+	pub insts: Vec<Instruction>,
+	/// how many bits of memory to give [Memory::new], at least one per output
+	pub bits: usize,
+	/// whether clocks or state bits can change the outputs without the inputs changing, \
+	/// if not running it again on the same inputs gives the same outputs
+	pub changes_by_itself: bool,
+	/// how many ticks it takes for every clock to be back where it started, 1 without clocks \
+	/// u64::MAX if that's more ticks than fit in one
+	pub clock_period: u64,
+}
+
+/// the lcm of the periods of every [Instruction::SummonClock], see [Compiled::clock_period]
+pub fn clock_period(insts: &[Instruction]) -> u64 {
+	fn gcd(a: u64, b: u64) -> u64 {
+		if b == 0 {
+			a
+		} else {
+			gcd(b, a % b)
+		}
+	}
+	insts
+		.iter()
+		.filter_map(|inst| match *inst {
+			Instruction::SummonClock { period, .. } => Some(period.max(1) as u64),
+			_ => None,
+		})
+		.try_fold(1_u64, |lcm, period| {
+			lcm.checked_mul(period / gcd(lcm, period))
+		})
+		.unwrap_or(u64::MAX)
+}
+
+/// one past the highest bit any of the instructions points to
+pub fn footprint(insts: &[Instruction]) -> usize {
+	insts
+		.iter()
+		.map(|inst| match *inst {
+			Instruction::SummonInput { out, .. }
+			| Instruction::SummonClock { out, .. }
+			| Instruction::SummonState { out, .. } => out,
+			Instruction::StoreState { ptr, .. } | Instruction::Set { ptr, .. } => ptr,
+			Instruction::Not { ptr, out } => ptr.max(out),
+			Instruction::Or { a, b, out }
+			| Instruction::And { a, b, out }
+			| Instruction::Xor { a, b, out } => a.max(b).max(out),
+			Instruction::Copy { src_ptr, dst_ptr } => src_ptr.max(dst_ptr),
+		} + 1)
+		.max()
+		.unwrap_or(0)
+}
+
+// essentially a computer. has some memory, runs instructions which change the memory
+// implementation's pretty basic and straightforward (for now)
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Memory {
+	/// as many bits as the program it runs needs, see [Compiled::bits]
+	mem: Vec<bool>,
+	/// how many ticks the instance running the program has been around for, used by [Instruction::SummonClock]
+	tick: u64,
+	/// bits that outlive an execution, see [Instruction::SummonState]
+	state: Vec<bool>,
+}
+impl Index<Range<usize>> for Memory {
+	type Output = [bool];
+	fn index(&self, index: Range<usize>) -> &Self::Output {
+		Index::index(&self.mem, index)
+	}
+}
+impl Memory {
+	pub fn new(bits: usize) -> Self {
+		Self {
+			mem: vec![false; bits],
+			..Default::default()
+		}
+	}
+	pub fn bits(&self) -> usize {
+		self.mem.len()
+	}
+	pub fn get(&mut self, i: usize) -> bool {
+		self.mem[i]
+	}
+	pub fn set(&mut self, i: usize, v: bool) {
+		self.mem[i] = v;
+	}
+	pub fn set_tick(&mut self, tick: u64) {
+		self.tick = tick;
+	}
+	/// the state bits the next execution starts with, missing ones are false
+	pub fn set_state(&mut self, state: Vec<bool>) {
+		self.state = state;
+	}
+	pub fn state(&self) -> &[bool] {
+		&self.state
+	}
+	pub fn take_state(&mut self) -> Vec<bool> {
+		std::mem::take(&mut self.state)
+	}
+
+	/// errors without running anything if the instructions point past the end of the memory
+	pub fn execute(&mut self, instructions: &[Instruction], inputs: &[bool]) -> anyhow::Result<()> {
+		let needed = footprint(instructions);
+		if needed > self.bits() {
+			return Err(anyhow!(
+				"the instructions use {needed} bits of memory, but there's only {}",
+				self.bits()
+			));
+		}
+
+		for inst in instructions {
+			match inst {
+				&Instruction::SummonInput { id, out } => {
+					if let Some(val) = inputs.iter().nth(id).copied() {
+						self.set(out, val)
+					} else {
+						eprintln!("program tried to access an input that doesn't exist");
+						self.set(out, false)
+					}
+				}
+				&Instruction::SummonClock { period, phase, out } => {
+					let period = period.max(1) as u64;
+					self.set(out, (self.tick + phase as u64).is_multiple_of(period))
+				}
+				&Instruction::SummonState { id, out } => {
+					let val = self.state.get(id).copied().unwrap_or(false);
+					self.set(out, val)
+				}
+				&Instruction::StoreState { ptr, id } => {
+					if self.state.len() <= id {
+						self.state.resize(id + 1, false);
+					}
+					self.state[id] = self.get(ptr);
+				}
+
+				&Instruction::Not { ptr, out } => {
+					let val = self.get(ptr);
+
+					self.set(out, !val)
+				}
+				&Instruction::Or { a, b, out } => {
+					let a = self.get(a);
+					let b = self.get(b);
+
+					self.set(out, a || b)
+				}
+				&Instruction::Set { ptr, val } => self.set(ptr, val),
+				&Instruction::Copy { src_ptr, dst_ptr } => {
+					let val = self.get(src_ptr);
+					self.set(dst_ptr, val)
+				}
+
+				&Instruction::And { a, b, out } => {
+					let a = self.get(a);
+					let b = self.get(b);
+
+					self.set(out, a && b);
+				}
+				&Instruction::Xor { a, b, out } => {
+					let a = self.get(a);
+					let b = self.get(b);
+
+					self.set(out, a ^ b);
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Instruction {
+	// base set
+	SummonInput {
+		id: usize,
+		out: usize,
+	},
+	/// true if a clock with the given period and phase would pulse at the current tick \
+	/// ticks count from when the instance was made, like a simulated clock's do, see [Memory::set_tick]
+	SummonClock {
+		period: u32,
+		phase: u32,
+		out: usize,
+	},
+	/// the value state bit `id` was stored with, by the previous execution
+	SummonState {
+		id: usize,
+		out: usize,
+	},
+	/// stores a value for the next execution to summon \
+	/// programs store their state bits last, so every summon in one execution sees the previous one's
+	StoreState {
+		ptr: usize,
+		id: usize,
+	},
+
+	Or {
+		a: usize,
+		b: usize,
+		out: usize,
+	},
+	/// flip the value in place
+	Not {
+		ptr: usize,
+		out: usize,
+	},
+	Set {
+		ptr: usize,
+		val: bool,
+	},
+	Copy {
+		src_ptr: usize,
+		dst_ptr: usize,
+	},
+
+	// extended set
+	And {
+		a: usize,
+		b: usize,
+		out: usize,
+	},
+	Xor {
+		a: usize,
+		b: usize,
+		out: usize,
+	},
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_processor_and() {
+		let mut mem = Memory::new(3);
+		let mut and_in_processor = |a: bool, b: bool| -> bool {
+			let instructions = [
+				Instruction::SummonInput { id: 0, out: 0 },
+				Instruction::SummonInput { id: 1, out: 1 },
+				Instruction::Not { ptr: 0, out: 0 },
+				Instruction::Not { ptr: 1, out: 1 },
+				Instruction::Or { a: 0, b: 1, out: 2 },
+				Instruction::Not { ptr: 2, out: 2 },
+			];
+			// 2 = 0 && 1
+
+			mem.execute(&instructions, &[a, b]).unwrap();
+
+			mem.get(2)
+		};
+
+		assert_eq!(and_in_processor(false, false), false);
+		assert_eq!(and_in_processor(true, false), false);
+		assert_eq!(and_in_processor(false, true), false);
+		assert_eq!(and_in_processor(true, true), true);
+	}
+
+	#[test]
+	fn test_processor_xor() {
+		let mut mem = Memory::new(5);
+		let mut xor_in_processor = |a: bool, b: bool| -> bool {
+			// make this and also make a couple of other logic gates in instructions by hand just to get a sense for the instructionset we'll need
+
+			// memory:
+			// 0,1: inputs
+			// 2: 0 && 1
+			// 3: 0 || 1
+			// 4: output, 3 && !2
+
+			let instructions = [
+				Instruction::SummonInput { id: 0, out: 0 },
+				Instruction::SummonInput { id: 1, out: 1 },
+				Instruction::And { a: 0, b: 1, out: 2 },
+				Instruction::Or { a: 0, b: 1, out: 3 },
+				Instruction::Not { ptr: 2, out: 2 },
+				Instruction::And { a: 3, b: 2, out: 4 },
+			];
+
+			mem.execute(&instructions, &[a, b]).unwrap();
+			let extended_set_result = mem.get(4);
+
+			extended_set_result
+		};
+
+		assert_eq!(xor_in_processor(false, false), false);
+		assert_eq!(xor_in_processor(true, false), true);
+		assert_eq!(xor_in_processor(false, true), true);
+		assert_eq!(xor_in_processor(true, true), false);
+	}
+
+	#[test]
+	fn test_processor_clock() {
+		let mut mem = Memory::new(1);
+		let instructions = [Instruction::SummonClock {
+			period: 3,
+			phase: 1,
+			out: 0,
+		}];
+
+		let pulses = (0..6)
+			.map(|tick| {
+				mem.set_tick(tick);
+				mem.execute(&instructions, &[]).unwrap();
+				mem.get(0)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(pulses, [false, false, true, false, false, true]);
+	}
+
+	#[test]
+	fn too_little_memory_errors() {
+		let instructions = [
+			Instruction::SummonInput { id: 0, out: 0 },
+			Instruction::Not { ptr: 0, out: 4 },
+		];
+		assert_eq!(footprint(&instructions), 5);
+
+		let mut mem = Memory::new(4);
+		assert!(mem.execute(&instructions, &[true]).is_err());
+		// nothing ran
+		assert!(!mem.get(0));
+
+		let mut mem = Memory::new(5);
+		mem.execute(&instructions, &[true]).unwrap();
+		assert!(!mem.get(4));
+	}
+}
@@ -0,0 +1,352 @@
+//! a pass over finished instructions, for what's easier to see there than in equations \
+//! every bit of memory gets turned into the value it holds (so copies disappear and summoning the same input
+//! twice is the same value), values nothing ends up needing are dropped, and what's left gets put back into as
+//! few bits as possible, a bit being reused as soon as the value in it isn't needed anymore
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context;
+
+use super::Instruction;
+
+/// what a bit holds, operands being indices of other values
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Value {
+	Input(usize),
+	Clock(u32, u32),
+	State(usize),
+	Const(bool),
+	Not(usize),
+	/// operands are sorted, so a || b and b || a are the same value
+	Or(usize, usize),
+	And(usize, usize),
+	Xor(usize, usize),
+}
+impl Value {
+	fn operands(self) -> impl Iterator<Item = usize> {
+		let (a, b) = match self {
+			Value::Input(_) | Value::Clock(..) | Value::State(_) | Value::Const(_) => (None, None),
+			Value::Not(a) => (Some(a), None),
+			Value::Or(a, b) | Value::And(a, b) | Value::Xor(a, b) => (Some(a), Some(b)),
+		};
+		a.into_iter().chain(b)
+	}
+	fn to_inst(self, slot_of: &[usize], out: usize) -> Instruction {
+		match self {
+			Value::Input(id) => Instruction::SummonInput { id, out },
+			Value::Clock(period, phase) => Instruction::SummonClock { period, phase, out },
+			Value::State(id) => Instruction::SummonState { id, out },
+			Value::Const(val) => Instruction::Set { ptr: out, val },
+			Value::Not(a) => Instruction::Not {
+				ptr: slot_of[a],
+				out,
+			},
+			Value::Or(a, b) => Instruction::Or {
+				a: slot_of[a],
+				b: slot_of[b],
+				out,
+			},
+			Value::And(a, b) => Instruction::And {
+				a: slot_of[a],
+				b: slot_of[b],
+				out,
+			},
+			Value::Xor(a, b) => Instruction::Xor {
+				a: slot_of[a],
+				b: slot_of[b],
+				out,
+			},
+		}
+	}
+}
+
+/// every distinct value the program computes, in the order it first computes them
+#[derive(Debug, Default)]
+struct Values {
+	values: Vec<Value>,
+	numbers: HashMap<Value, usize>,
+}
+impl Values {
+	fn number(&mut self, value: Value) -> usize {
+		*self.numbers.entry(value).or_insert_with(|| {
+			self.values.push(value);
+			self.values.len() - 1
+		})
+	}
+	fn pair(&mut self, a: usize, b: usize, f: fn(usize, usize) -> Value) -> usize {
+		self.number(f(a.min(b), a.max(b)))
+	}
+}
+
+/// makes a program run fewer instructions on fewer bits of memory, without changing what it does \
+/// the first `outputs_len` bits end up the same as after the original, and every state bit is stored with the
+/// same value. every other bit is only scratch space \
+/// errors if the program reads a bit before writing to it, since then it'd depend on what the last execution left there
+pub fn optimize(insts: &[Instruction], outputs_len: usize) -> anyhow::Result<Vec<Instruction>> {
+	let mut values = Values::default();
+	// K: bit, V: value
+	let mut slots = HashMap::<usize, usize>::new();
+	// K: state id, V: value, sorted so the program always stores in the same order
+	let mut stored = BTreeMap::<usize, usize>::new();
+
+	for (i, inst) in insts.iter().enumerate() {
+		let read = |ptr: usize| {
+			slots.get(&ptr).copied().with_context(|| {
+				format!("instruction {i} ({inst:?}) reads bit {ptr} before it's written to")
+			})
+		};
+		let (out, value) = match *inst {
+			Instruction::SummonInput { id, out } => (out, values.number(Value::Input(id))),
+			Instruction::SummonClock { period, phase, out } => {
+				(out, values.number(Value::Clock(period, phase)))
+			}
+			// a state stored earlier in the same execution is already the new value
+			Instruction::SummonState { id, out } => match stored.get(&id) {
+				Some(value) => (out, *value),
+				None => (out, values.number(Value::State(id))),
+			},
+			Instruction::StoreState { ptr, id } => {
+				stored.insert(id, read(ptr)?);
+				continue;
+			}
+			Instruction::Not { ptr, out } => (out, values.number(Value::Not(read(ptr)?))),
+			Instruction::Or { a, b, out } => (out, values.pair(read(a)?, read(b)?, Value::Or)),
+			Instruction::And { a, b, out } => (out, values.pair(read(a)?, read(b)?, Value::And)),
+			Instruction::Xor { a, b, out } => (out, values.pair(read(a)?, read(b)?, Value::Xor)),
+			Instruction::Set { ptr, val } => (ptr, values.number(Value::Const(val))),
+			Instruction::Copy { src_ptr, dst_ptr } => (dst_ptr, read(src_ptr)?),
+		};
+		slots.insert(out, value);
+	}
+	let values = values.values;
+
+	// (bit, value), bits the original never wrote to are left alone
+	let outputs = (0..outputs_len)
+		.filter_map(|bit| slots.get(&bit).map(|value| (bit, *value)))
+		.collect::<Vec<_>>();
+
+	// values are only ever made out of earlier ones, so going backwards sees every user before what it uses
+	const FOREVER: usize = usize::MAX;
+	let mut last_use = vec![None; values.len()];
+	for value in outputs.iter().map(|(_, v)| v).chain(stored.values()) {
+		last_use[*value] = Some(FOREVER);
+	}
+	for (v, value) in values.iter().enumerate().rev() {
+		if last_use[v].is_some() {
+			for operand in value.operands() {
+				let used = last_use[operand].get_or_insert(v);
+				*used = (*used).max(v);
+			}
+		}
+	}
+
+	// an output's value is computed right into its bit, if the same value is another output too it's copied
+	let mut pinned = HashMap::<usize, usize>::new();
+	let mut copies = vec![];
+	for (bit, value) in outputs {
+		match pinned.get(&value) {
+			Some(src_ptr) => copies.push(Instruction::Copy {
+				src_ptr: *src_ptr,
+				dst_ptr: bit,
+			}),
+			None => {
+				pinned.insert(value, bit);
+			}
+		}
+	}
+
+	let mut vec = vec![];
+	let mut slot_of = vec![0; values.len()];
+	let mut free = vec![];
+	let mut next_free = outputs_len;
+	for (v, value) in values.iter().enumerate() {
+		if last_use[v].is_none() {
+			continue;
+		}
+		// freed before picking a bit, so an operand's bit can be written over by the value using it last
+		for operand in value.operands() {
+			if last_use[operand] == Some(v) && !pinned.contains_key(&operand) {
+				last_use[operand] = None;
+				free.push(slot_of[operand]);
+			}
+		}
+		let out = match pinned.get(&v) {
+			Some(bit) => *bit,
+			None => {
+				free.sort_unstable_by(|a, b| b.cmp(a));
+				free.pop().unwrap_or_else(|| {
+					next_free += 1;
+					next_free - 1
+				})
+			}
+		};
+		slot_of[v] = out;
+		vec.push(value.to_inst(&slot_of, out));
+	}
+	for (id, value) in stored {
+		vec.push(Instruction::StoreState {
+			ptr: slot_of[value],
+			id,
+		});
+	}
+	vec.extend(copies);
+
+	Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::processor::{
+		eq::Equation,
+		footprint,
+		world_to_instructions::{sequential_to_instructions, Sequential},
+		Memory,
+	};
+
+	/// the outputs and the stored state bits after running, for every combination of inputs and state bits,
+	/// at a few different ticks
+	fn behaviour(
+		insts: &[Instruction],
+		inputs: usize,
+		states: usize,
+		outputs: usize,
+	) -> Vec<(Vec<bool>, Vec<bool>)> {
+		let bits = |n: usize, len: usize| (0..len).map(|i| n >> i & 1 == 1).collect::<Vec<_>>();
+		let mut vec = vec![];
+		for tick in 0..4 {
+			for i in 0..1 << inputs {
+				for s in 0..1 << states {
+					let mut mem = Memory::new(footprint(insts).max(outputs));
+					mem.set_tick(tick);
+					mem.set_state(bits(s, states));
+					mem.execute(insts, &bits(i, inputs)).unwrap();
+					vec.push((mem[0..outputs].to_vec(), mem.take_state()));
+				}
+			}
+		}
+		vec
+	}
+
+	/// a pseudo random equation over `inputs` inputs and `states` state bits
+	fn random_eq(seed: &mut u32, depth: u32, inputs: usize, states: usize) -> Equation {
+		*seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+		let roll = *seed >> 16;
+		if depth == 0 {
+			return match roll % 8 {
+				0 => Equation::State(roll as usize / 8 % states),
+				1 => Equation::Clock {
+					period: 2 + roll / 8 % 2,
+					phase: 0,
+				},
+				2 => Equation::Const(roll & 8 == 0),
+				_ => Equation::Input(roll as usize / 8 % inputs),
+			};
+		}
+		match roll % 4 {
+			0 => Equation::not(random_eq(seed, depth - 1, inputs, states)),
+			1 => Equation::all(
+				[
+					random_eq(seed, depth - 1, inputs, states),
+					random_eq(seed, depth - 1, inputs, states),
+				]
+				.into_iter(),
+			),
+			_ => Equation::or(
+				random_eq(seed, depth - 1, inputs, states),
+				random_eq(seed, depth - 1, inputs, states),
+			),
+		}
+	}
+
+	#[test]
+	fn random_programs_do_the_same() {
+		let (inputs, states, outputs) = (4, 2, 3);
+		let mut seed = 1;
+		for _ in 0..50 {
+			// the same equation a few times over, so the shareds make copies
+			let shared = random_eq(&mut seed, 3, inputs, states);
+			let eq =
+				|seed: &mut u32| Equation::or(shared.clone(), random_eq(seed, 2, inputs, states));
+			let sequential = Sequential {
+				outputs: (0..outputs).map(|_| eq(&mut seed)).collect(),
+				states: (0..states).map(|_| eq(&mut seed)).collect(),
+			};
+
+			let insts = sequential_to_instructions(sequential).unwrap();
+			let optimized = optimize(&insts, outputs).unwrap();
+			assert!(optimized.len() <= insts.len());
+			assert_eq!(
+				behaviour(&insts, inputs, states, outputs),
+				behaviour(&optimized, inputs, states, outputs),
+				"{insts:#?}\n{optimized:#?}"
+			);
+		}
+	}
+
+	#[test]
+	fn loads_inputs_once_and_skips_copies() {
+		let insts = [
+			Instruction::SummonInput { id: 0, out: 0 },
+			Instruction::SummonInput { id: 0, out: 5 },
+			Instruction::Copy {
+				src_ptr: 5,
+				dst_ptr: 6,
+			},
+			Instruction::Not { ptr: 6, out: 6 },
+			Instruction::Or { a: 0, b: 6, out: 1 },
+			// never used
+			Instruction::SummonInput { id: 1, out: 7 },
+			Instruction::Copy {
+				src_ptr: 1,
+				dst_ptr: 2,
+			},
+		];
+
+		assert_eq!(
+			optimize(&insts, 3).unwrap(),
+			[
+				Instruction::SummonInput { id: 0, out: 0 },
+				Instruction::Not { ptr: 0, out: 3 },
+				Instruction::Or { a: 0, b: 3, out: 1 },
+				Instruction::Copy {
+					src_ptr: 1,
+					dst_ptr: 2,
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn reuses_bits() {
+		// (i0 || i1) && (i2 || i3), with every value in its own bit
+		let insts = [
+			Instruction::SummonInput { id: 0, out: 1 },
+			Instruction::SummonInput { id: 1, out: 2 },
+			Instruction::Or { a: 1, b: 2, out: 3 },
+			Instruction::SummonInput { id: 2, out: 4 },
+			Instruction::SummonInput { id: 3, out: 5 },
+			Instruction::Or { a: 4, b: 5, out: 6 },
+			Instruction::And { a: 3, b: 6, out: 0 },
+		];
+
+		let optimized = optimize(&insts, 1).unwrap();
+		let bits = optimized
+			.iter()
+			.filter_map(|inst| match *inst {
+				Instruction::SummonInput { out, .. }
+				| Instruction::Or { out, .. }
+				| Instruction::And { out, .. } => Some(out),
+				_ => None,
+			})
+			.max();
+		assert_eq!(bits, Some(3));
+		assert_eq!(behaviour(&insts, 4, 0, 1), behaviour(&optimized, 4, 0, 1));
+	}
+
+	#[test]
+	fn reading_unwritten_bits_errors() {
+		let insts = [Instruction::Not { ptr: 3, out: 0 }];
+		assert!(optimize(&insts, 1).is_err());
+	}
+}
@@ -68,7 +68,7 @@ fn replace_with_shared_if_needed(
 			}
 		}
 		Equation::Shared(_) => eprintln!("unexpected shared while finding sharables: {eq:#?}"),
-		Equation::Const(_) | Equation::Input(_) => (),
+		Equation::Const(_) | Equation::Input(_) | Equation::Clock { .. } | Equation::State(_) => (),
 	}
 
 	if to_share.contains(&hash) {
@@ -111,7 +111,10 @@ fn add_to_appearances(eq: &Equation, appearances: &mut Vec<(u64, i32)>) {
 			Equation::Shared(sh) => sh
 				.store
 				.with_borrow(|data| add_to_appearances(&data.eq, appearances)),
-			Equation::Const(_) | Equation::Input(_) => (),
+			Equation::Const(_)
+			| Equation::Input(_)
+			| Equation::Clock { .. }
+			| Equation::State(_) => (),
 		}
 	}
 }
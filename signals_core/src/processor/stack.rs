@@ -18,9 +18,11 @@
 // reserve: reserve bits of memory for later use
 // check-in: claim a reserved bit for use
 
+use super::store::Store;
+
 #[derive(Clone, Debug)]
 pub struct Stack {
-	shared: sui::core::Store<StackShared>,
+	shared: Store<StackShared>,
 	short_term: usize,
 }
 impl Stack {
@@ -30,7 +32,7 @@ impl Stack {
 	}
 	pub fn with_reserved(start_ptr: usize, reserve: usize) -> Self {
 		Self {
-			shared: sui::core::Store::new(StackShared {
+			shared: Store::new(StackShared {
 				stack_bottom: start_ptr,
 				reserved: reserve,
 			}),
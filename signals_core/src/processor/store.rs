@@ -0,0 +1,23 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// a shared, mutable piece of data \
+/// cloning a store clones the reference, so every clone sees the same data
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Store<T>(Rc<RefCell<T>>);
+impl<T> Clone for Store<T> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+impl<T> Store<T> {
+	pub fn new(data: T) -> Self {
+		Self(Rc::new(RefCell::new(data)))
+	}
+
+	pub fn with_borrow<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+		f(&self.0.borrow())
+	}
+	pub fn with_mut_borrow<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+		f(&mut self.0.borrow_mut())
+	}
+}
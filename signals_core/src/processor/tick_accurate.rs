@@ -0,0 +1,646 @@
+//! compiling a world so its program takes exactly as many ticks as simulating it would \
+//! a simulated tick only depends on the moves made the tick before and the state of the blocks,
+//! so those become state bits, and one execution of the program does what one tick of [World::tick] does
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::{
+	game::{Game, WorldId},
+	world::{Block, Direction, Received, World},
+};
+
+use super::{eq::Equation, world_to_instructions::Sequential};
+
+/// what a state bit stands for
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+	/// a signal made last tick moving into the block from `from`
+	Move((i32, i32), Direction),
+	/// one line of a bus signal made last tick moving into the block from `from`
+	BusMove((i32, i32), Direction, u8),
+	/// a transmitter on the channel got a signal last tick
+	Channel(u8),
+	/// (inst_id, id) the instance's output, which gets to the foreign block the tick after it's made
+	FromForeign(usize, usize),
+	/// the nth bit a block keeps, like what a latch stores
+	Block((i32, i32), u8),
+	/// true after the first tick, for blocks that start out doing something different
+	Started,
+	/// (inst_id, id) a state bit of a foreign instance
+	Foreign(usize, usize),
+}
+
+/// one tick of a world, built up block by block
+#[derive(Debug, Default)]
+struct Tick {
+	/// (what it stands for, what it is before the first tick), the index being the state bit \
+	/// a bit is stored xor'd with its starting value, so every bit starts out false like the memory does
+	bits: Vec<(Key, bool)>,
+	ids: HashMap<Key, usize>,
+	/// what each bit is after this tick, moves coming from different blocks get or'd together
+	next: HashMap<Key, Equation>,
+	/// one per output id
+	outputs: Vec<Equation>,
+	/// (inst_id, id) what goes into the instances this tick, they tick right after the world they're in
+	to_foreigns: HashMap<(usize, usize), Equation>,
+}
+impl Tick {
+	fn read(&mut self, key: Key) -> Equation {
+		self.read_from(key, false)
+	}
+	/// the value of the bit before this tick, `initial` being what it is before the first one
+	fn read_from(&mut self, key: Key, initial: bool) -> Equation {
+		let id = match self.ids.get(&key) {
+			Some(id) => *id,
+			None => {
+				self.bits.push((key, initial));
+				self.ids.insert(key, self.bits.len() - 1);
+				self.bits.len() - 1
+			}
+		};
+		match self.bits[id].1 {
+			true => Equation::not(Equation::State(id)),
+			false => Equation::State(id),
+		}
+	}
+	fn set(&mut self, key: Key, eq: Equation) {
+		self.next.insert(key, eq);
+	}
+	fn or(&mut self, key: Key, eq: Equation) {
+		let prev = self.next.remove(&key).unwrap_or(Equation::Const(false));
+		self.next.insert(key, Equation::any([prev, eq].into_iter()));
+	}
+	/// whether this is the first tick
+	fn first(&mut self) -> Equation {
+		Equation::not(self.read(Key::Started))
+	}
+
+	/// whether a signal moved into the block from any of the directions
+	fn arrived(&mut self, coords: (i32, i32), from: impl Iterator<Item = Direction>) -> Equation {
+		let arrivals = from
+			.map(|from| self.read(Key::Move(coords, from)))
+			.collect::<Vec<_>>();
+		Equation::any(arrivals.into_iter())
+	}
+	/// a signal moving from the block to its neighbour in `dir`, arriving there next tick
+	fn emit(&mut self, (x, y): (i32, i32), dir: Direction, eq: Equation) {
+		let (r_x, r_y) = dir.rel();
+		self.or(Key::Move((x + r_x, y + r_y), dir.reverse()), eq);
+	}
+	fn emit_all(&mut self, coords: (i32, i32), eq: Equation) {
+		for dir in Direction::all() {
+			self.emit(coords, dir, eq.clone());
+		}
+	}
+	fn emit_bus(&mut self, (x, y): (i32, i32), dir: Direction, bit: u8, eq: Equation) {
+		let (r_x, r_y) = dir.rel();
+		self.or(Key::BusMove((x + r_x, y + r_y), dir.reverse(), bit), eq);
+	}
+	/// the bits of a bus coming in from `from`
+	fn bus_arrived(
+		&mut self,
+		coords: (i32, i32),
+		from: impl Iterator<Item = Direction>,
+		bit: u8,
+	) -> Equation {
+		let arrivals = from
+			.map(|from| self.read(Key::BusMove(coords, from, bit)))
+			.collect::<Vec<_>>();
+		Equation::any(arrivals.into_iter())
+	}
+
+	/// what the block does with the signals moving into it, and what it does on its own, see [Block::pass] and [Block::tick]
+	fn block(&mut self, world: &World, coords: (i32, i32), block: Block) {
+		let all = Direction::all;
+		match block {
+			Block::Wire(dir) => {
+				let arrived = self.arrived(coords, all().filter(|from| *from != dir));
+				self.emit(coords, dir, arrived);
+			}
+			Block::Router => {
+				let arrived = self.arrived(coords, all());
+				self.emit_all(coords, arrived);
+			}
+			Block::Junction => {
+				for from in all() {
+					let arrived = self.read(Key::Move(coords, from));
+					self.emit(coords, from.reverse(), arrived);
+				}
+			}
+			Block::Not(received) => {
+				let arrived = self.arrived(coords, all());
+				// a not that starts out received stays quiet for the first tick
+				let quiet = match received {
+					true => Equation::or(arrived, self.first()),
+					false => arrived,
+				};
+				self.emit_all(coords, Equation::not(quiet));
+			}
+			Block::Delay(dir, ticks, held) => {
+				let ticks = ticks.clamp(1, Block::DELAY_MAX);
+				let len = ticks.max(32 - held.leading_zeros() as u8);
+				let arrived = self.arrived(coords, all().filter(|from| *from != dir));
+				// what it holds once this tick's signal is in, the lowest bit gets passed on
+				let held = (0..len)
+					.map(|i| {
+						let bit = self.read_from(Key::Block(coords, i), held >> i & 1 == 1);
+						match i == ticks - 1 {
+							true => Equation::or(bit, arrived.clone()),
+							false => bit,
+						}
+					})
+					.collect::<Vec<_>>();
+				self.emit(coords, dir, held[0].clone());
+				for i in 0..len {
+					let next = held
+						.get(i as usize + 1)
+						.cloned()
+						.unwrap_or(Equation::Const(false));
+					self.set(Key::Block(coords, i), next);
+				}
+			}
+			Block::Latch(dir, received, stored) => {
+				let (back, side) = self.stateful_inputs(coords, dir, received);
+				let prev = self.read_from(Key::Block(coords, 0), stored);
+				// reset wins
+				let stored =
+					Equation::all([Equation::not(side), Equation::or(back, prev)].into_iter());
+				self.emit(coords, dir, stored.clone());
+				self.set(Key::Block(coords, 0), stored);
+			}
+			Block::FlipFlop(dir, received, prev_clock, stored) => {
+				let (back, side) = self.stateful_inputs(coords, dir, received);
+				let prev = self.read_from(Key::Block(coords, 0), stored);
+				let prev_clock = self.read_from(Key::Block(coords, 1), prev_clock);
+				let rising = Equation::all([side.clone(), Equation::not(prev_clock)].into_iter());
+				let stored = Equation::or(
+					Equation::all([rising.clone(), back].into_iter()),
+					Equation::all([Equation::not(rising), prev].into_iter()),
+				);
+				self.emit(coords, dir, stored.clone());
+				self.set(Key::Block(coords, 0), stored);
+				self.set(Key::Block(coords, 1), side);
+			}
+			Block::Switch(on) => self.emit_all(coords, Equation::Const(on)),
+			Block::Clock { period, phase } => {
+				let pulse = self.clock(coords, period, phase);
+				self.emit_all(coords, pulse);
+			}
+			// buttons aren't pressed outside the main world, but they keep however they were placed
+			Block::Button {
+				pulse: false,
+				pressed: true,
+			} => self.emit_all(coords, Equation::Const(true)),
+			Block::Button {
+				pulse: true,
+				pressed: true,
+			} => {
+				let first = self.first();
+				self.emit_all(coords, first);
+			}
+			// only the input the world's inputs are sent to, see [World::find_input]
+			Block::Input(id) if world.find_input(id) == Some(coords) => {
+				self.emit_all(coords, Equation::Input(id))
+			}
+			Block::Output(id) => {
+				let arrived = self.arrived(coords, all());
+				if let Some(output) = self.outputs.get_mut(id) {
+					let prev = std::mem::replace(output, Equation::Const(false));
+					*output = Equation::any([prev, arrived].into_iter());
+				}
+			}
+			Block::Transmitter(channel) => {
+				let arrived = self.arrived(coords, all());
+				self.or(Key::Channel(channel), arrived);
+			}
+			Block::Receiver(channel) => {
+				let received = self.read(Key::Channel(channel));
+				self.emit_all(coords, received);
+			}
+			Block::Bus(dir, width) => {
+				for bit in 0..width.min(Block::BUS_MAX_WIDTH) {
+					let arrived = self.bus_arrived(coords, all().filter(|from| *from != dir), bit);
+					self.emit_bus(coords, dir, bit, arrived);
+				}
+			}
+			Block::Splitter(dir, s_bit) => {
+				for bit in 0..Block::BUS_MAX_WIDTH {
+					let arrived = self.bus_arrived(coords, [dir.reverse()].into_iter(), bit);
+					if bit == s_bit {
+						self.emit(coords, dir.rotate_l(), arrived.clone());
+						self.emit(coords, dir.rotate_r(), arrived.clone());
+					}
+					self.emit_bus(coords, dir, bit, arrived);
+				}
+			}
+			Block::Merger(dir, m_bit) => {
+				for bit in 0..Block::BUS_MAX_WIDTH {
+					let mut arrived = self.bus_arrived(coords, [dir.reverse()].into_iter(), bit);
+					if bit == m_bit {
+						let side =
+							self.arrived(coords, [dir.rotate_l(), dir.rotate_r()].into_iter());
+						arrived = Equation::or(arrived, side);
+					}
+					self.emit_bus(coords, dir, bit, arrived);
+				}
+			}
+			Block::Foreign(_, inst_id, id) => {
+				let arrived = self.arrived(coords, all());
+				let prev = self
+					.to_foreigns
+					.remove(&(inst_id, id))
+					.unwrap_or(Equation::Const(false));
+				self.to_foreigns
+					.insert((inst_id, id), Equation::any([prev, arrived].into_iter()));
+
+				// the instance's outputs come out of the foreign its inputs go into, but not into other foreigns
+				if world.find_foreign(inst_id, id) == Some(coords) {
+					let from_foreign = self.read(Key::FromForeign(inst_id, id));
+					for dir in all() {
+						let (r_x, r_y) = dir.rel();
+						match world.at(coords.0 + r_x, coords.1 + r_y) {
+							None | Some(Block::Foreign(..)) => (),
+							Some(_) => self.emit(coords, dir, from_foreign.clone()),
+						}
+					}
+				}
+			}
+			Block::Input(_)
+			| Block::Nothing
+			| Block::Error(_)
+			| Block::Display(..)
+			| Block::Button { .. } => (),
+		}
+	}
+	/// (back, either side) going into a latch or flip-flop, `received` is what it got before the first tick
+	fn stateful_inputs(
+		&mut self,
+		coords: (i32, i32),
+		dir: Direction,
+		received: Received,
+	) -> (Equation, Equation) {
+		let mut back = self.arrived(coords, [dir.reverse()].into_iter());
+		let mut side = self.arrived(coords, [dir.rotate_l(), dir.rotate_r()].into_iter());
+		if received.back {
+			back = Equation::or(back, self.first());
+		}
+		if received.side {
+			side = Equation::or(side, self.first());
+		}
+		(back, side)
+	}
+	/// whether the clock pulses this tick \
+	/// counts the ticks since the phase it started at, so the counter starts out at 0 like every other bit
+	fn clock(&mut self, coords: (i32, i32), period: u32, phase: u32) -> Equation {
+		let period = period.max(1);
+		if period == 1 {
+			return Equation::Const(true);
+		}
+		let len = 32 - (period - 1).leading_zeros() as u8;
+		let count = (0..len)
+			.map(|i| self.read(Key::Block(coords, i)))
+			.collect::<Vec<_>>();
+		let is = |n: u32| {
+			Equation::all(count.iter().enumerate().map(|(i, bit)| match n >> i & 1 {
+				1 => bit.clone(),
+				_ => Equation::not(bit.clone()),
+			}))
+		};
+
+		let wraps = is(period - 1);
+		let mut carry = Equation::Const(true);
+		for (i, bit) in count.iter().enumerate() {
+			let next = Equation::or(
+				Equation::all([bit.clone(), Equation::not(carry.clone())].into_iter()),
+				Equation::all([Equation::not(bit.clone()), carry.clone()].into_iter()),
+			);
+			self.set(
+				Key::Block(coords, i as u8),
+				Equation::all([Equation::not(wraps.clone()), next].into_iter()),
+			);
+			carry = Equation::all([bit.clone(), carry].into_iter());
+		}
+		is((period - phase % period) % period)
+	}
+
+	/// the state bits in order, with the ones nothing ever turns on left out
+	fn finish(mut self) -> Sequential {
+		if self.ids.contains_key(&Key::Started) {
+			self.set(Key::Started, Equation::Const(true));
+		}
+
+		let next = self
+			.bits
+			.iter()
+			.map(|(key, initial)| {
+				let next = self
+					.next
+					.remove(key)
+					.unwrap_or(Equation::Const(false))
+					.simplify();
+				match initial {
+					true => Equation::not(next).simplify(),
+					false => next,
+				}
+			})
+			.collect::<Vec<_>>();
+
+		// like moves into blocks nothing ever passes anything to
+		let mut ids = vec![None; next.len()];
+		let mut kept = 0;
+		for (id, next) in next.iter().enumerate() {
+			if *next != Equation::Const(false) {
+				ids[id] = Some(kept);
+				kept += 1;
+			}
+		}
+		let renumber = |eq: Equation| {
+			eq.map_states(&|id| match ids[id] {
+				Some(id) => Equation::State(id),
+				None => Equation::Const(false),
+			})
+			.simplify()
+		};
+
+		Sequential {
+			outputs: self.outputs.into_iter().map(renumber).collect(),
+			states: next
+				.into_iter()
+				.zip(ids.iter())
+				.filter(|(_, id)| id.is_some())
+				.map(|(next, _)| renumber(next))
+				.collect(),
+		}
+	}
+}
+
+/// the world as equations that take as long as the simulation does, see the module docs \
+/// foreigns are inlined with the same timing instances have, they get their inputs the tick the world passes
+/// them to the foreign, and their outputs get to the world the tick after
+pub fn world_to_tick_accurate(game: &Game, world_id: WorldId) -> anyhow::Result<Sequential> {
+	world_to_tick_accurate_internal(game, world_id, &mut HashMap::new())
+}
+
+fn world_to_tick_accurate_internal(
+	game: &Game,
+	world_id: WorldId,
+	inlined: &mut HashMap<WorldId, Sequential>,
+) -> anyhow::Result<Sequential> {
+	let world = game
+		.worlds
+		.at(world_id)
+		.with_context(|| format!("no world with id {world_id:?}"))?;
+
+	let mut tick = Tick {
+		outputs: vec![Equation::Const(false); world.outputs_count()],
+		..Default::default()
+	};
+	for (coords, block) in world.blocks() {
+		tick.block(world, coords, *block);
+	}
+
+	let mut instances = world
+		.find_foreigns()
+		.map(|(_, (wid, inst_id, _))| (inst_id, wid))
+		.collect::<Vec<_>>();
+	instances.sort();
+	instances.dedup_by_key(|(inst_id, _)| *inst_id);
+	for (inst_id, wid) in instances {
+		let foreign = match inlined.get(&wid) {
+			Some(foreign) => foreign.clone(),
+			None => {
+				let foreign = world_to_tick_accurate_internal(game, wid, inlined)
+					.with_context(|| format!("while inlining instance {inst_id} of {wid:?}"))?;
+				inlined.insert(wid, foreign.clone());
+				foreign
+			}
+		};
+
+		let ids = (0..foreign.states.len())
+			.map(|f_id| match tick.read(Key::Foreign(inst_id, f_id)) {
+				Equation::State(id) => id,
+				_ => unreachable!("state bits of instances start out false"),
+			})
+			.collect::<Vec<_>>();
+		let here = |eq: Equation| {
+			eq.map_states(&|f_id| Equation::State(ids[f_id]))
+				.map_inputs(|id| {
+					anyhow::Ok(
+						tick.to_foreigns
+							.get(&(inst_id, id))
+							.cloned()
+							.unwrap_or(Equation::Const(false)),
+					)
+				})
+		};
+
+		let nexts = foreign
+			.states
+			.into_iter()
+			.map(here)
+			.collect::<anyhow::Result<Vec<_>>>()?;
+		let outputs = foreign
+			.outputs
+			.into_iter()
+			.map(here)
+			.collect::<anyhow::Result<Vec<_>>>()?;
+		for (f_id, next) in nexts.into_iter().enumerate() {
+			tick.set(Key::Foreign(inst_id, f_id), next);
+		}
+		for (id, output) in outputs.into_iter().enumerate() {
+			tick.or(Key::FromForeign(inst_id, id), output);
+		}
+	}
+
+	Ok(tick.finish())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		game::IngameWorldType,
+		processor::Timing,
+		world::{Move, Signal},
+	};
+
+	/// a main world with `instances` instances of `wid` side by side, passing `inputs` inputs into each
+	/// and their `outputs` outputs back out, one instance's after the other's
+	fn main_around(
+		game: &mut Game,
+		wid: WorldId,
+		instances: usize,
+		inputs: usize,
+		outputs: usize,
+	) -> WorldId {
+		let main = game.push();
+		let world = game.worlds.at_mut(main).unwrap();
+		for inst_id in 0..instances {
+			let x = inst_id as i32 * 4;
+			for id in 0..inputs.max(outputs) {
+				let y = id as i32 * 2;
+				if id < inputs {
+					*world.mut_at(x, y) = Block::Input(inst_id * inputs + id);
+				}
+				*world.mut_at(x + 1, y) = Block::Foreign(wid, inst_id, id);
+				if id < outputs {
+					*world.mut_at(x + 2, y) = Block::Output(inst_id * outputs + id);
+				}
+			}
+		}
+		main
+	}
+
+	/// the outputs of the main world every tick, with the inputs changing pseudo randomly
+	fn outputs_over_time(game: &mut Game, inputs: usize, ticks: usize) -> Vec<Vec<usize>> {
+		let mut seed = 7u32;
+		(0..ticks)
+			.map(|_| {
+				seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+				let on = (0..inputs).filter(|id| seed >> (16 + id) & 1 == 1);
+				game.moves.receive_moves(on.map(|id| Move::Input {
+					id,
+					signal: Signal::ExternalPoweron,
+				}));
+
+				let mut outputs = vec![];
+				game.tick_with_outputs(|id| outputs.push(id)).unwrap();
+				outputs.sort();
+				outputs
+			})
+			.collect()
+	}
+
+	/// ticks `wid` simulated and then as a tick accurate processor, they have to do the same every tick
+	fn assert_same_timing(
+		mut game: Game,
+		wid: WorldId,
+		instances: usize,
+		inputs: usize,
+		outputs: usize,
+	) {
+		let main = main_around(&mut game, wid, instances, inputs, outputs);
+		game.main_id = main;
+
+		let mut simulated = game.clone();
+		simulated.regenerate_moves(main).unwrap();
+		assert!(simulated
+			.moves
+			.children
+			.iter()
+			.all(|child| matches!(child.typ, IngameWorldType::Simulated { .. })));
+
+		let mut processor = game;
+		processor.set_timing(Timing::TickAccurate).unwrap();
+		assert!(processor
+			.moves
+			.children
+			.iter()
+			.all(|child| matches!(child.typ, IngameWorldType::Processor { .. })));
+
+		let inputs = instances * inputs;
+		assert_eq!(
+			outputs_over_time(&mut simulated, inputs, 100),
+			outputs_over_time(&mut processor, inputs, 100)
+		);
+	}
+
+	#[test]
+	fn same_timing_as_simulated() {
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		let blocks = [
+			((0, 0), Block::Input(0)),
+			((1, 0), Block::Wire(Direction::Right)),
+			((2, 0), Block::Junction),
+			((3, 0), Block::Delay(Direction::Right, 3, 0)),
+			((4, 0), Block::Router),
+			((5, 0), Block::Wire(Direction::Right)),
+			(
+				(6, 0),
+				Block::Latch(Direction::Right, Received::NONE, false),
+			),
+			((7, 0), Block::Output(0)),
+			// the router also goes through a transmitter into the latch's side
+			((4, 1), Block::Wire(Direction::Bottom)),
+			((4, 2), Block::Transmitter(1)),
+			((6, 2), Block::Receiver(1)),
+			((6, 1), Block::Wire(Direction::Top)),
+			// a flip-flop clocked by a not of input 1, through the junction
+			((0, 3), Block::Input(1)),
+			((1, 3), Block::Wire(Direction::Right)),
+			((2, 3), Block::Not(false)),
+			((2, 2), Block::Wire(Direction::Top)),
+			((2, 1), Block::Wire(Direction::Top)),
+			((0, -1), Block::Wire(Direction::Right)),
+			((1, -1), Block::Wire(Direction::Right)),
+			(
+				(2, -1),
+				Block::FlipFlop(Direction::Right, Received::NONE, false, false),
+			),
+			((3, -1), Block::Wire(Direction::Right)),
+			((4, -1), Block::Output(1)),
+			// a clock going over a bus
+			(
+				(3, 3),
+				Block::Clock {
+					period: 3,
+					phase: 1,
+				},
+			),
+			((3, 4), Block::Wire(Direction::Bottom)),
+			((3, 5), Block::Merger(Direction::Right, 2)),
+			((4, 5), Block::Bus(Direction::Right, 4)),
+			((5, 5), Block::Bus(Direction::Right, 4)),
+			((6, 5), Block::Splitter(Direction::Right, 2)),
+			((6, 6), Block::Output(2)),
+		];
+		for (coords, block) in blocks {
+			*world.mut_at(coords.0, coords.1) = block;
+		}
+
+		assert_same_timing(game, wid, 1, 2, 3);
+	}
+
+	#[test]
+	fn instances_inside_keep_their_timing() {
+		let mut game = Game::default();
+		let inner = game.push();
+		let world = game.worlds.at_mut(inner).unwrap();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(2, 0) = Block::Not(false);
+		*world.mut_at(3, 0) = Block::Output(0);
+
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(2, 0) = Block::Foreign(inner, 0, 0);
+		*world.mut_at(3, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(4, 0) = Block::Foreign(inner, 1, 0);
+		*world.mut_at(5, 0) = Block::Output(0);
+
+		assert_same_timing(game, wid, 1, 1, 1);
+	}
+
+	#[test]
+	fn instances_side_by_side_keep_their_timing() {
+		// a latch set by input 0 and reset by input 1, through a delay
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Delay(Direction::Right, 2, 0);
+		*world.mut_at(2, 0) = Block::Latch(Direction::Right, Received::NONE, false);
+		*world.mut_at(3, 0) = Block::Output(0);
+		*world.mut_at(2, 2) = Block::Input(1);
+		*world.mut_at(2, 1) = Block::Wire(Direction::Top);
+
+		// every instance has to end up in its own lane
+		assert_same_timing(game, wid, 3, 2, 1);
+	}
+}
@@ -0,0 +1,988 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context};
+
+use crate::{
+	game::{Game, WorldId},
+	processor::eq::Equation,
+	world::{Block, Direction, World},
+};
+
+use super::{
+	clock_period, eq::ForeignRef, footprint, optimize, program, stack::Stack, store::Store,
+	tick_accurate, Compiled, Instruction, Timing, MAX_BITS,
+};
+
+/// returns none if world doesn't exist \
+/// outputs end up at the start of the memory \
+/// follows [Game::timing], errors if running it would take more than [MAX_BITS]
+pub fn world_to_instructions(game: &Game, world_id: WorldId) -> anyhow::Result<Compiled> {
+	let sequential = match game.timing {
+		Timing::Instant => world_to_sequential(game, world_id)?,
+		Timing::TickAccurate => tick_accurate::world_to_tick_accurate(game, world_id)?,
+	};
+	let outputs_len = sequential.outputs.len();
+	let insts = sequential_to_instructions(sequential)?;
+	let insts =
+		optimize::optimize(&insts, outputs_len).with_context(|| "while optimizing instructions")?;
+
+	// outputs the program never writes to are still read after running it
+	let bits = footprint(&insts).max(outputs_len);
+	if bits > MAX_BITS {
+		return Err(anyhow!(
+			"world {world_id} needs {bits} bits of memory, more than the {MAX_BITS} a processor can have"
+		));
+	}
+	let changes_by_itself = insts.iter().any(|inst| {
+		matches!(
+			inst,
+			Instruction::SummonClock { .. } | Instruction::SummonState { .. }
+		)
+	});
+	Ok(Compiled {
+		clock_period: clock_period(&insts),
+		insts,
+		bits,
+		changes_by_itself,
+	})
+}
+
+/// the instructions before [optimize::optimize] \
+/// outputs end up at the start of the memory, followed by the next value of every state bit
+pub fn sequential_to_instructions(
+	Sequential { outputs, states }: Sequential,
+) -> anyhow::Result<Vec<Instruction>> {
+	let mut vec = vec![];
+	let outputs_len = outputs.len();
+	let states_len = states.len();
+
+	let program = outputs
+		.into_iter()
+		.chain(states)
+		.map(Equation::simplify)
+		.collect();
+	let program = program::shared_recognition(program);
+
+	let reservations = {
+		let mut reservations = 0;
+		let mut map = Default::default();
+		for eq in program.iter() {
+			let to_add = Equation::reservations_internal(eq, &mut map);
+			reservations += to_add;
+		}
+		reservations
+	};
+	let stack = Stack::with_reserved(outputs_len + states_len, reservations);
+
+	for (i, eq) in program.iter().enumerate() {
+		eq.to_insts(i, stack.clone(), &mut vec)
+			.with_context(|| format!("error while turning eq into insts for output {i}"))?;
+	}
+	// only once everything's been computed, so every eq saw the previous state
+	for id in 0..states_len {
+		vec.push(Instruction::StoreState {
+			ptr: outputs_len + id,
+			id,
+		});
+	}
+
+	Ok(vec)
+}
+
+/// what a world does over time as equations, with every foreign inlined \
+/// every execution computes the outputs and the next value of each state bit
+/// from the inputs and what the state bits were after the previous execution
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sequential {
+	/// one per output
+	pub outputs: Vec<Equation>,
+	/// one per state bit, [Equation::State] with the same index is the value it had last time
+	pub states: Vec<Equation>,
+}
+
+/// what a state bit stands for
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum StateKey {
+	/// what the block passed towards `from` last time, to break up a loop going through it
+	Loop((i32, i32), Option<Direction>),
+	/// what a latch or flip-flop stores
+	Stored((i32, i32)),
+	/// whether the clock of a flip-flop was on, so it can tell when it goes from off to on
+	PrevClock((i32, i32)),
+	/// (inst_id, id) a state bit of a foreign instance
+	Foreign(usize, usize),
+}
+
+/// the state bits found while turning a world into equations
+#[derive(Debug, Default)]
+struct States {
+	/// (what it stands for, its next value once that's been generated, whether that has foreigns inlined)
+	bits: Vec<(StateKey, Option<Equation>, bool)>,
+	ids: HashMap<StateKey, usize>,
+}
+impl States {
+	/// the id of the bit, adding it if it's new
+	fn id(&mut self, key: StateKey) -> usize {
+		match self.ids.get(&key) {
+			Some(id) => *id,
+			None => {
+				self.bits.push((key, None, false));
+				self.ids.insert(key, self.bits.len() - 1);
+				self.bits.len() - 1
+			}
+		}
+	}
+	/// the bit's value after the previous execution
+	fn bit(&mut self, key: StateKey) -> Equation {
+		Equation::State(self.id(key))
+	}
+	fn missing(&self) -> Option<(usize, StateKey)> {
+		self.bits
+			.iter()
+			.position(|(_, next, _)| next.is_none())
+			.map(|id| (id, self.bits[id].0))
+	}
+}
+
+/// every world inlined so far, so instances of the same world don't get generated again
+type Inlined = Store<HashMap<WorldId, Option<Sequential>>>;
+
+pub fn world_to_sequential(game: &Game, world_id: WorldId) -> anyhow::Result<Sequential> {
+	let world = game
+		.worlds
+		.at(world_id)
+		.with_context(|| format!("no world with id {world_id:?}"))?;
+	let states = Store::new(States::default());
+
+	let mut outputs = vec![];
+	for i in 0..world.outputs().count() {
+		let coords = world
+			.outputs()
+			.find(|(id, _)| *id == i)
+			.map(|(_, coords)| coords)
+			.with_context(|| format!("no output with id {i} in world {world_id:?}"))?;
+		let eq = block_to_eq_internal(world, coords, None, vec![], &states)
+			.with_context(|| format!("error while generating eq for output {i}"))?;
+		outputs.push(eq.simplify());
+	}
+
+	// next values can run into loops of their own, which need more state bits
+	while let Some((id, key)) = states.with_borrow(States::missing) {
+		let next = next_state_eq(world, key, &states)
+			.with_context(|| format!("error while generating eq for state bit {id} ({key:?})"))?;
+		states.with_mut_borrow(|states| states.bits[id].1 = Some(next.simplify()));
+	}
+
+	let inlined = Inlined::default();
+	let outputs = outputs
+		.into_iter()
+		.map(|eq| inline_foreigns(game, eq, &states, &inlined))
+		.collect::<anyhow::Result<Vec<_>>>()?;
+	// inlining adds the state bits of the instances, which are inlined already
+	let mut id = 0;
+	while id < states.with_borrow(|states| states.bits.len()) {
+		let next = states.with_mut_borrow(|states| {
+			let (_, next, inlined) = &mut states.bits[id];
+			match std::mem::replace(inlined, true) {
+				true => None,
+				false => next.take(),
+			}
+		});
+		if let Some(next) = next {
+			let next = inline_foreigns(game, next, &states, &inlined)?;
+			states.with_mut_borrow(|states| states.bits[id].1 = Some(next));
+		}
+		id += 1;
+	}
+	let states = states.with_mut_borrow(|states| {
+		states
+			.bits
+			.drain(..)
+			.map(|(_, next, _)| next.unwrap_or(Equation::Const(false)))
+			.collect()
+	});
+
+	Ok(Sequential { outputs, states })
+}
+
+/// the output's equation, state bits show up as [Equation::State], see [world_to_sequential]
+pub fn world_output_to_eq(game: &Game, world_id: WorldId, id: usize) -> anyhow::Result<Equation> {
+	world_to_sequential(game, world_id)?
+		.outputs
+		.get(id)
+		.cloned()
+		.with_context(|| format!("no output with id {id} in world {world_id:?}"))
+}
+
+/// returns whether that given block in a world is on or off as an equation \
+/// state bits show up as [Equation::State], but what they'll be next isn't part of it
+pub fn world_block_to_eq(
+	game: &Game,
+	world_id: WorldId,
+	coords: (i32, i32),
+) -> anyhow::Result<Equation> {
+	let world = game
+		.worlds
+		.at(world_id)
+		.with_context(|| "this world does not exist")?;
+
+	let states = Store::new(States::default());
+	let eq = block_to_eq_internal(world, coords, None, vec![], &states)?.simplify();
+	inline_foreigns(game, eq, &states, &Inlined::default())
+}
+
+/// --- this is the part that inlines all the foreigns \
+/// the state bits of every instance become state bits of this world
+fn inline_foreigns(
+	game: &Game,
+	eq: Equation,
+	states: &Store<States>,
+	inlined: &Inlined,
+) -> anyhow::Result<Equation> {
+	let eq = eq
+		.map_foreigns(|w_id, inst_id, id, in_eqs| {
+			let w_id = match w_id {
+				ForeignRef::Foreign(w_id) => w_id,
+			};
+			let foreign = inlined.with_borrow(|inlined| inlined.get(&w_id).cloned());
+			let foreign = match foreign {
+				Some(foreign) => foreign,
+				None => {
+					let foreign = match world_to_sequential(game, w_id) {
+						Ok(foreign) => Some(foreign),
+						Err(err) => {
+							eprintln!("{err}\nusing Const(false) instead");
+							None
+						}
+					};
+					inlined.with_mut_borrow(|inlined| inlined.insert(w_id, foreign.clone()));
+					foreign
+				}
+			};
+			let Some(Sequential {
+				outputs,
+				states: f_states,
+			}) = foreign
+			else {
+				return Ok(Equation::Const(false));
+			};
+
+			let ids = states.with_mut_borrow(|states| {
+				(0..f_states.len())
+					.map(|f_id| states.id(StateKey::Foreign(inst_id, f_id)))
+					.collect::<Vec<_>>()
+			});
+			let here = |eq: Equation| {
+				eq.map_states(&|f_id| Equation::State(ids[f_id]))
+					.map_inputs(|id| {
+						let f_input = in_eqs.get(id).cloned().unwrap_or(Equation::Const(false));
+						anyhow::Ok(f_input)
+					})
+			};
+
+			// the first time an instance gets inlined decides what its state bits do
+			for (f_id, next) in f_states.into_iter().enumerate() {
+				if states.with_borrow(|states| states.bits[ids[f_id]].1.is_none()) {
+					let next = here(next)?;
+					states.with_mut_borrow(|states| {
+						states.bits[ids[f_id]] =
+							(StateKey::Foreign(inst_id, f_id), Some(next), true)
+					});
+				}
+			}
+			here(outputs.get(id).cloned().unwrap_or(Equation::Const(false)))
+		})?
+		.simplify();
+
+	Ok(eq)
+}
+
+/// what a state bit is going to be after this execution
+fn next_state_eq(world: &World, key: StateKey, states: &Store<States>) -> anyhow::Result<Equation> {
+	match key {
+		StateKey::Loop(coords, from) => block_to_eq_internal(world, coords, from, vec![], states),
+		StateKey::Stored(coords) => stored_eq(world, coords, vec![], states),
+		StateKey::PrevClock(coords) => match world.at(coords.0, coords.1) {
+			Some(&Block::FlipFlop(dir, ..)) => {
+				Ok(stateful_inputs(world, coords, dir, vec![], states)?.1)
+			}
+			_ => Err(anyhow!("no flip-flop at ({}, {})", coords.0, coords.1)),
+		},
+		StateKey::Foreign(..) => Err(anyhow!("state bits of foreigns come with the instance")),
+	}
+}
+
+/// what a latch or flip-flop stores after this execution, which is also what it outputs
+fn stored_eq(
+	world: &World,
+	coords: (i32, i32),
+	circular_check: Vec<((i32, i32), Option<Direction>)>,
+	states: &Store<States>,
+) -> anyhow::Result<Equation> {
+	let stored = states.with_mut_borrow(|states| states.bit(StateKey::Stored(coords)));
+	match world.at(coords.0, coords.1) {
+		Some(&Block::Latch(dir, ..)) => {
+			let (set, reset) = stateful_inputs(world, coords, dir, circular_check, states)?;
+			// reset wins
+			Ok(Equation::all(
+				[Equation::not(reset), Equation::or(set, stored)].into_iter(),
+			))
+		}
+		Some(&Block::FlipFlop(dir, ..)) => {
+			let (data, clock) = stateful_inputs(world, coords, dir, circular_check, states)?;
+			let prev_clock =
+				states.with_mut_borrow(|states| states.bit(StateKey::PrevClock(coords)));
+			let rising = Equation::all([clock, Equation::not(prev_clock)].into_iter());
+			Ok(Equation::or(
+				Equation::all([rising.clone(), data].into_iter()),
+				Equation::all([Equation::not(rising), stored].into_iter()),
+			))
+		}
+		_ => Err(anyhow!(
+			"no latch or flip-flop at ({}, {})",
+			coords.0,
+			coords.1
+		)),
+	}
+}
+
+/// (back, either side) going into a latch or flip-flop facing `dir`
+fn stateful_inputs(
+	world: &World,
+	(b_x, b_y): (i32, i32),
+	dir: Direction,
+	mut circular_check: Vec<((i32, i32), Option<Direction>)>,
+	states: &Store<States>,
+) -> anyhow::Result<(Equation, Equation)> {
+	// getting back to the block itself means getting what it stored last time
+	circular_check.push(((b_x, b_y), Some(dir)));
+	let (r_x, r_y) = dir.reverse().rel();
+	let back = block_to_eq_internal(
+		world,
+		(b_x + r_x, b_y + r_y),
+		Some(dir),
+		circular_check.clone(),
+		states,
+	)?;
+
+	let mut side = Equation::Const(false);
+	for side_dir in [dir.rotate_l(), dir.rotate_r()] {
+		let (r_x, r_y) = side_dir.rel();
+		let eq = block_to_eq_internal(
+			world,
+			(b_x + r_x, b_y + r_y),
+			Some(side_dir.reverse()),
+			circular_check.clone(),
+			states,
+		)?;
+		side = Equation::any([side, eq].into_iter());
+	}
+	Ok((back, side))
+}
+
+fn block_to_eq_internal(
+	world: &World,
+	(b_x, b_y): (i32, i32),
+	from: Option<Direction>,
+	mut circular_check: Vec<((i32, i32), Option<Direction>)>, // <- really inefficient workaround alert!!!
+	states: &Store<States>,
+) -> anyhow::Result<Equation> {
+	let b = if let Some(b) = world.at(b_x, b_y) {
+		b
+	} else {
+		eprintln!("no such block in this world");
+		return Ok(Equation::Const(false));
+	};
+
+	if circular_check.contains(&((b_x, b_y), from)) {
+		// this case means we've already been to this block before, and now we're here again.
+		// this means a circular dependency, except if this is a wire pointing a direction that doesn't matter.
+		// (in which case we return const(false) anyway)
+		// yeah handling edge cases is fun
+		// a real loop gets broken up here by using what the block passed last execution instead
+
+		match b {
+			Block::Wire(dir) if from.map(|from| from != *dir).unwrap_or(false) => {
+				// doesn't even matter we'll return false in a couple of nanoseconds
+			}
+			// which is just what they store
+			Block::Latch(..) | Block::FlipFlop(..) => {
+				return Ok(
+					states.with_mut_borrow(|states| states.bit(StateKey::Stored((b_x, b_y))))
+				);
+			}
+			_ => {
+				return Ok(
+					states.with_mut_borrow(|states| states.bit(StateKey::Loop((b_x, b_y), from)))
+				);
+			}
+		}
+	}
+	circular_check.push(((b_x, b_y), from));
+
+	// next up is a precise specification for each block because we need feature parity between realtime and computed mode
+
+	let all_directions_except = |except: Option<Direction>| {
+		let potential_sources = Direction::all()
+			.filter(|dir| except.map(|except| *dir != except).unwrap_or(true))
+			.map(|dir| (dir.reverse(), dir.rel()))
+			.map(|(from, (r_x, r_y))| (from, (b_x + r_x, b_y + r_y)));
+
+		let mut eq = Equation::Const(false);
+		for (from, coords) in potential_sources {
+			eq = Equation::any(
+				[
+					eq,
+					block_to_eq_internal(world, coords, Some(from), circular_check.clone(), states)
+						.with_context(|| format!("{b_x} {b_y} -> {} {}", coords.0, coords.1))?,
+				]
+				.into_iter(),
+			);
+		}
+		Ok(eq)
+	};
+	let all_directions = || {
+		let from =    from.with_context(|| "blocks that could receive signals from any direction should not be called without a from argument")?;
+		all_directions_except(Some(from))
+	};
+
+	match b {
+		&Block::Wire(base_dir) => {
+			if from.map(|from| from != base_dir).unwrap_or(false) {
+				// if from points to a block which this wire would not actually pass a signal to
+				return Ok(Equation::Const(false));
+			}
+
+			let (mut w_x, mut w_y) = (b_x, b_y);
+			// we're tracing backwards so one step in base_dir.reverse() every iteration
+
+			let mut eq = Equation::Const(false);
+			loop {
+				let back_dir = base_dir.reverse();
+				let (r_x, r_y) = back_dir.rel();
+
+				let behind = world.at(w_x, w_y);
+
+				match behind {
+					Some(&Block::Wire(behind_dir)) if base_dir == behind_dir => {
+						let left_dir = base_dir.rotate_l();
+						let right_dir = base_dir.rotate_r();
+						let (left, right) = (left_dir.rel(), right_dir.rel());
+						let left = (w_x + left.0, w_y + left.1);
+						let right = (w_x + right.0, w_y + right.1);
+
+						let left = block_to_eq_internal(
+							world,
+							left,
+							Some(left_dir.reverse()),
+							circular_check.clone(),
+							states,
+						)?;
+						let right = block_to_eq_internal(
+							world,
+							right,
+							Some(right_dir.reverse()),
+							circular_check.clone(),
+							states,
+						)?;
+
+						eq = Equation::any([eq, left, right].into_iter());
+
+						w_x += r_x;
+						w_y += r_y;
+					}
+					_ => {
+						let b_eq = block_to_eq_internal(
+							world,
+							(w_x, w_y),
+							Some(base_dir),
+							circular_check.clone(),
+							states,
+						)?;
+						break Ok(Equation::any([eq, b_eq].into_iter()));
+					}
+				}
+			}
+		}
+		Block::Not(_) => {
+			// nots work differently in evaluated vs real time mode
+
+			let base = all_directions()?;
+			Ok(Equation::not(base))
+		}
+		Block::Junction => {
+			if let Some(from) = from {
+				let (r_x, r_y) = from.reverse().rel();
+				block_to_eq_internal(
+					world,
+					(b_x + r_x, b_y + r_y),
+					Some(from),
+					circular_check.clone(),
+					states,
+				)
+			} else {
+				Err(anyhow!(
+					"tried to turn junction into eq without passing from arg"
+				))
+			}
+		}
+		&Block::Delay(dir, _, _) => {
+			// delays only affect timing, which the processor doesn't care about,
+			// so a delay is the same as a wire pointing the same way
+			if from == Some(dir) {
+				all_directions_except(Some(dir))
+			} else {
+				Ok(Equation::Const(false))
+			}
+		}
+		// they only output to the front
+		&Block::Latch(dir, ..) | &Block::FlipFlop(dir, ..) if from == Some(dir) => {
+			stored_eq(world, (b_x, b_y), circular_check.clone(), states)
+		}
+		Block::Latch(..) | Block::FlipFlop(..) => Ok(Equation::Const(false)),
+		Block::Router => all_directions(),
+		Block::Input(id) => Ok(Equation::Input(*id)),
+		Block::Switch(val) => Ok(Equation::Const(*val)),
+		&Block::Clock { period, phase } => Ok(Equation::Clock { period, phase }),
+		Block::Output(_) if from.is_none() => all_directions_except(None), // start case
+		// asked by a receiver on the same channel
+		Block::Transmitter(_) if from.is_none() => all_directions_except(None),
+		&Block::Splitter(dir, bit)
+			if from == Some(dir.rotate_l()) || from == Some(dir.rotate_r()) =>
+		{
+			let (r_x, r_y) = dir.reverse().rel();
+			bus_to_eq_internal(
+				world,
+				(b_x + r_x, b_y + r_y),
+				Some(dir),
+				bit,
+				circular_check.clone(),
+				states,
+			)
+		}
+		&Block::Receiver(channel) => {
+			let mut eq = Equation::Const(false);
+			for coords in world.find_transmitters(channel) {
+				eq = Equation::any(
+					[
+						eq,
+						block_to_eq_internal(world, coords, None, circular_check.clone(), states)
+							.with_context(|| {
+							format!("channel {channel} -> {} {}", coords.0, coords.1)
+						})?,
+					]
+					.into_iter(),
+				);
+			}
+			Ok(eq)
+		}
+		// buttons can't be pressed outside the main world
+		Block::Nothing
+		| Block::Error(_)
+		| Block::Output(_)
+		| Block::Transmitter(_)
+		| Block::Bus(..)
+		| Block::Splitter(..)
+		| Block::Merger(..)
+		| Block::Display(..)
+		| Block::Button { .. } => Ok(Equation::Const(false)),
+		&Block::Foreign(wid, inst_id, id) => {
+			let foreign_inputs =
+				foreign_inputs(world, inst_id, id, from, circular_check.clone(), states)?;
+
+			Ok(Equation::Foreign(
+				ForeignRef::Foreign(wid),
+				inst_id,
+				id,
+				foreign_inputs,
+			))
+		}
+	}
+}
+
+/// like [block_to_eq_internal], but for a single line of a bus \
+/// returns whether `bit` of the bus this block passes towards `from` is on
+fn bus_to_eq_internal(
+	world: &World,
+	(b_x, b_y): (i32, i32),
+	from: Option<Direction>,
+	bit: u8,
+	mut circular_check: Vec<((i32, i32), Option<Direction>)>,
+	states: &Store<States>,
+) -> anyhow::Result<Equation> {
+	let b = match world.at(b_x, b_y) {
+		Some(b) => *b,
+		None => return Ok(Equation::Const(false)),
+	};
+
+	// bits aren't part of the check, so a bus passing two bits of the same path through one block counts as circular too
+	if circular_check.contains(&((b_x, b_y), from)) {
+		return Err(anyhow!("this world has a circular dependency through a bus, starting from ({b_x}, {b_y})\npath taken: {circular_check:#?}"));
+	}
+	circular_check.push(((b_x, b_y), from));
+
+	let behind = |dir: Direction| {
+		let (r_x, r_y) = dir.reverse().rel();
+		(b_x + r_x, b_y + r_y)
+	};
+
+	match b {
+		Block::Bus(dir, width) => {
+			if from != Some(dir) || bit >= width {
+				return Ok(Equation::Const(false));
+			}
+			// same as wires, buses accept signals from every direction except the front
+			let mut eq = Equation::Const(false);
+			for src_dir in Direction::all().filter(|src_dir| *src_dir != dir) {
+				let (r_x, r_y) = src_dir.rel();
+				let src = bus_to_eq_internal(
+					world,
+					(b_x + r_x, b_y + r_y),
+					Some(src_dir.reverse()),
+					bit,
+					circular_check.clone(),
+					states,
+				)?;
+				eq = Equation::any([eq, src].into_iter());
+			}
+			Ok(eq)
+		}
+		Block::Splitter(dir, _) | Block::Merger(dir, _) if from != Some(dir) => {
+			Ok(Equation::Const(false))
+		}
+		Block::Splitter(dir, _) => bus_to_eq_internal(
+			world,
+			behind(dir),
+			Some(dir),
+			bit,
+			circular_check.clone(),
+			states,
+		),
+		Block::Merger(dir, m_bit) => {
+			let mut eq = bus_to_eq_internal(
+				world,
+				behind(dir),
+				Some(dir),
+				bit,
+				circular_check.clone(),
+				states,
+			)?;
+			if bit == m_bit {
+				for side in [dir.rotate_l(), dir.rotate_r()] {
+					let (r_x, r_y) = side.rel();
+					let line = block_to_eq_internal(
+						world,
+						(b_x + r_x, b_y + r_y),
+						Some(side.reverse()),
+						circular_check.clone(),
+						states,
+					)?;
+					eq = Equation::any([eq, line].into_iter());
+				}
+			}
+			Ok(eq)
+		}
+		// every other block only deals with single lines
+		_ => Ok(Equation::Const(false)),
+	}
+}
+
+fn foreign_inputs(
+	world: &World,
+	inst_id: usize,
+	because_id: usize,
+	because_from: Option<Direction>,
+	circular_check: Vec<((i32, i32), Option<Direction>)>,
+	states: &Store<States>,
+) -> anyhow::Result<Vec<Equation>> {
+	let foreigns = world.find_instance(inst_id);
+
+	let mut vec = vec![Equation::Const(false); foreigns.len()];
+
+	for (coords, (_, _, id)) in foreigns {
+		let total_eq = {
+			let directions = Direction::all().filter(|dir| {
+				if id == because_id {
+					because_from.map(|from| *dir != from).unwrap_or(false)
+				} else {
+					true
+				}
+			});
+			let a = directions
+				.map(|dir| (dir.reverse(), dir.rel()))
+				.map(|(from, (r_x, r_y))| (from, (coords.0 + r_x, coords.1 + r_y)));
+
+			let mut eq = Equation::Const(false);
+			for (from, coords) in a {
+				if let Block::Foreign(..) = world.at(coords.0, coords.1).unwrap_or(&Block::Nothing)
+				{
+					// foreigns don't pass signals to each other
+				} else {
+					eq = Equation::any(
+						[
+							eq,
+							block_to_eq_internal(
+								&world,
+								coords,
+								Some(from),
+								circular_check.clone(),
+								states,
+							)?,
+						]
+						.into_iter(),
+					);
+				}
+			}
+			eq
+		};
+
+		vec[id] = total_eq;
+	}
+
+	Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::*;
+	use super::*;
+
+	#[test]
+	fn equations_and() {
+		let and = Equation::not(Equation::or(
+			Equation::not(Equation::Input(0)),
+			Equation::not(Equation::Input(1)),
+		));
+
+		let mut insts = vec![];
+		and.to_insts(0, Stack::new(2), &mut insts)
+			.expect("no foreigns here");
+
+		let mut mem = Memory::new(footprint(&insts));
+
+		let mut run = |a: bool, b: bool| -> bool {
+			mem.execute(&insts, &[a, b]).unwrap();
+
+			mem.get(0)
+		};
+
+		assert_eq!(run(false, false), false);
+		assert_eq!(run(true, false), false);
+		assert_eq!(run(false, true), false);
+		assert_eq!(run(true, true), true);
+	}
+
+	#[test]
+	fn equations_xor() {
+		let xor = Equation::all(
+			[
+				Equation::or(Equation::Input(0), Equation::Input(1)),
+				Equation::not(Equation::all(
+					[Equation::Input(0), Equation::Input(1)].into_iter(),
+				)),
+			]
+			.into_iter(),
+		);
+
+		let mut insts = vec![];
+		xor.to_insts(0, Stack::new(2), &mut insts)
+			.expect("no foreigns here");
+
+		let mut mem = Memory::new(footprint(&insts));
+
+		let mut run = |a: bool, b: bool| -> bool {
+			mem.execute(&insts, &[a, b]).unwrap();
+			mem.get(0)
+		};
+
+		assert_eq!(run(false, false), false);
+		assert_eq!(run(true, false), true);
+		assert_eq!(run(false, true), true);
+		assert_eq!(run(true, true), false);
+	}
+
+	#[test]
+	fn test_generated_xor() {
+		let insts = [
+			Instruction::SummonInput { id: 0, out: 0 },
+			Instruction::SummonInput { id: 1, out: 2 },
+			Instruction::Or { a: 0, b: 2, out: 0 },
+			Instruction::SummonInput { id: 1, out: 1 },
+			Instruction::Not { ptr: 1, out: 1 },
+			Instruction::SummonInput { id: 0, out: 2 },
+			Instruction::Not { ptr: 2, out: 2 },
+			Instruction::Or { a: 1, b: 2, out: 1 },
+			Instruction::And { a: 0, b: 1, out: 0 },
+		];
+
+		let mut mem = Memory::new(footprint(&insts));
+
+		let mut run = |a: bool, b: bool| -> bool {
+			mem.execute(&insts, &[a, b]).unwrap();
+			mem.get(0)
+		};
+
+		assert_eq!(run(false, false), false);
+		assert_eq!(run(true, false), true);
+		assert_eq!(run(false, true), true);
+		assert_eq!(run(true, true), false);
+	}
+
+	/// runs the world's program once per entry of `inputs`, keeping the state bits in between
+	fn run_sequence(game: &Game, wid: WorldId, inputs: &[&[bool]]) -> Vec<Vec<bool>> {
+		let Compiled { insts, bits, .. } =
+			world_to_instructions(game, wid).expect("failed to gen instructions");
+		let outputs_len = game.worlds.at(wid).unwrap().outputs().count();
+		let mut state = vec![];
+		inputs
+			.iter()
+			.map(|inputs| {
+				let mut mem = Memory::new(bits);
+				mem.set_state(std::mem::take(&mut state));
+				mem.execute(&insts, inputs).unwrap();
+				state = mem.take_state();
+				mem[0..outputs_len].to_vec()
+			})
+			.collect()
+	}
+
+	/// a flip-flop whose data is its own output negated, so it flips every time input 0 turns on \
+	/// output 0 is the negated output
+	fn toggle(world: &mut World) {
+		*world.mut_at(0, 0) = Block::FlipFlop(Direction::Right, Default::default(), false, false);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Bottom);
+		*world.mut_at(1, 1) = Block::Not(false);
+		*world.mut_at(0, 1) = Block::Wire(Direction::Left);
+		*world.mut_at(-1, 1) = Block::Wire(Direction::Top);
+		*world.mut_at(-1, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(0, -1) = Block::Input(0);
+		*world.mut_at(2, 1) = Block::Output(0);
+	}
+
+	#[test]
+	fn latch_remembers() {
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Latch(Direction::Right, Default::default(), false);
+		*world.mut_at(-1, 0) = Block::Input(0); // set
+		*world.mut_at(0, -1) = Block::Input(1); // reset
+		*world.mut_at(1, 0) = Block::Output(0);
+
+		let outputs = run_sequence(
+			&game,
+			wid,
+			&[
+				&[true, false],
+				&[false, false],
+				&[false, true],
+				&[true, true],
+				&[true, false],
+				&[false, false],
+			],
+		);
+		let outputs = outputs.into_iter().map(|out| out[0]).collect::<Vec<_>>();
+		assert_eq!(outputs, [true, true, false, false, true, true]);
+	}
+
+	#[test]
+	fn flip_flop_counts() {
+		let mut game = Game::default();
+		let wid = game.push();
+		toggle(game.worlds.at_mut(wid).unwrap());
+
+		let clock: [&[bool]; 7] = [
+			&[true],
+			&[false],
+			&[true],
+			&[false],
+			&[true],
+			&[true],
+			&[false],
+		];
+		let outputs = run_sequence(&game, wid, &clock);
+		let outputs = outputs.into_iter().map(|out| out[0]).collect::<Vec<_>>();
+		assert_eq!(outputs, [false, false, true, true, false, false, false]);
+	}
+
+	#[test]
+	fn not_loop_toggles() {
+		let mut game = Game::default();
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		*world.mut_at(0, 0) = Block::Not(false);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Bottom);
+		*world.mut_at(1, 1) = Block::Wire(Direction::Left);
+		*world.mut_at(0, 1) = Block::Wire(Direction::Top);
+		*world.mut_at(-1, 0) = Block::Output(0);
+
+		let outputs = run_sequence(&game, wid, &[&[] as &[bool]; 6]);
+		for pair in outputs.windows(2) {
+			assert_ne!(pair[0], pair[1]);
+		}
+	}
+
+	#[test]
+	fn foreign_instances_keep_their_own_state() {
+		let mut game = Game::default();
+		let counter = game.push();
+		toggle(game.worlds.at_mut(counter).unwrap());
+
+		let wid = game.push();
+		let world = game.worlds.at_mut(wid).unwrap();
+		for (inst_id, y) in [(0, 0), (1, 5)] {
+			*world.mut_at(0, y) = Block::Input(inst_id);
+			*world.mut_at(1, y) = Block::Foreign(counter, inst_id, 0);
+			*world.mut_at(2, y) = Block::Output(inst_id);
+		}
+
+		let outputs = run_sequence(
+			&game,
+			wid,
+			&[
+				&[true, false],
+				&[false, false],
+				&[true, true],
+				&[false, false],
+			],
+		);
+		assert_eq!(
+			outputs,
+			[[false, true], [false, true], [true, false], [true, false]]
+		);
+	}
+
+	#[test]
+	fn foreign_test() {
+		let inside = Equation::all([Equation::Input(0), Equation::Input(1)].into_iter());
+
+		let outside = Equation::Foreign(
+			ForeignRef::Foreign(Default::default()),
+			0,
+			0,
+			vec![Equation::Input(2), Equation::Input(3)],
+		);
+
+		let total: Result<_, ()> = outside.map_foreigns(|_, _, _, in_eqs| {
+			inside.clone().map_inputs(|in_id| Ok(in_eqs[in_id].clone()))
+		});
+		let total = total.expect("oadigh");
+
+		let insts = total
+			.simplify()
+			.gen_insts(0, 4)
+			.expect("failed to gen instructions");
+
+		let mut mem = Memory::new(footprint(&insts));
+
+		let mut run = |a: bool, b: bool| -> bool {
+			mem.execute(&insts, &[false, false, a, b]).unwrap();
+			mem.get(0)
+		};
+
+		assert_eq!(run(false, false), false);
+		assert_eq!(run(true, false), false);
+		assert_eq!(run(false, true), false);
+		assert_eq!(run(true, true), true);
+	}
+}
@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Context};
+
+use crate::{
+	game::{saves, Game, WorldId, Worlds},
+	world::{Move, Signal},
+};
+
+/// runs the main world of a [Game] without anything graphical \
+/// inputs stay on until they're turned off, outputs are the ones that got a signal during the last tick
+#[derive(Clone, Debug, Default)]
+pub struct Simulator {
+	game: Game,
+	inputs: Vec<bool>,
+	outputs: Vec<bool>,
+}
+impl Simulator {
+	/// no world is selected as the main world, see [Simulator::set_main]
+	pub fn new(worlds: Worlds) -> anyhow::Result<Self> {
+		Ok(Self {
+			game: Game::from_worlds(worlds).with_context(|| "in Simulator::new")?,
+			..Default::default()
+		})
+	}
+	/// reads a `.snsv` file
+	pub fn read(path: &str) -> anyhow::Result<Self> {
+		Self::new(saves::read_worlds(path)?)
+	}
+	/// loads the contents of a `.snsv` file
+	pub fn load(bytes: &[u8]) -> anyhow::Result<Self> {
+		Self::new(saves::load_worlds(bytes)?)
+	}
+
+	pub fn game(&self) -> &Game {
+		&self.game
+	}
+	pub fn worlds(&self) -> &Worlds {
+		&self.game.worlds
+	}
+
+	/// selects the world to simulate, resetting every input, output and the state of the previous main world
+	pub fn set_main(&mut self, wid: WorldId) -> anyhow::Result<()> {
+		let world = self
+			.game
+			.worlds
+			.at(wid)
+			.with_context(|| format!("no world with id {wid}"))?;
+		self.inputs = vec![false; world.inputs_count()];
+		self.outputs = vec![false; world.outputs_count()];
+
+		self.game.main_id = wid;
+		self.game
+			.regenerate_moves(wid)
+			.with_context(|| format!("while making {wid} the main world"))?;
+		Ok(())
+	}
+	pub fn main_id(&self) -> WorldId {
+		self.game.main_id
+	}
+
+	/// the input stays `on` until it's set again
+	pub fn set_input(&mut self, id: usize, on: bool) -> anyhow::Result<()> {
+		match self.inputs.get_mut(id) {
+			Some(input) => {
+				*input = on;
+				Ok(())
+			}
+			None => Err(anyhow!(
+				"the main world only has {} inputs, there's no input {id}",
+				self.inputs.len()
+			)),
+		}
+	}
+	pub fn inputs(&self) -> &[bool] {
+		&self.inputs
+	}
+
+	/// ticks the main world `ticks` times
+	pub fn step(&mut self, ticks: usize) -> anyhow::Result<()> {
+		for _ in 0..ticks {
+			let inputs = self
+				.inputs
+				.iter()
+				.enumerate()
+				.filter(|(_, on)| **on)
+				.map(|(id, _)| Move::Input {
+					id,
+					signal: Signal::ExternalPoweron,
+				});
+			self.game.moves.receive_moves(inputs);
+
+			let outputs = &mut self.outputs;
+			outputs.iter_mut().for_each(|out| *out = false);
+			self.game
+				.tick_with_outputs(|id| match outputs.get_mut(id) {
+					Some(out) => *out = true,
+					None => eprintln!("the main world returned output {id}, which it doesn't have"),
+				})?;
+		}
+		Ok(())
+	}
+
+	pub fn output(&self, id: usize) -> Option<bool> {
+		self.outputs.get(id).copied()
+	}
+	pub fn outputs(&self) -> &[bool] {
+		&self.outputs
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::world::{Block, Direction, World};
+
+	#[test]
+	fn simulator_passes_inputs_to_outputs() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Right);
+		*world.mut_at(2, 0) = Block::Not(false);
+		*world.mut_at(3, 0) = Block::Output(0);
+		let mut worlds = Worlds::default();
+		let wid = worlds.push(world);
+
+		let mut sim = Simulator::new(worlds).unwrap();
+		sim.set_main(wid).unwrap();
+
+		sim.step(5).unwrap();
+		assert_eq!(sim.outputs(), &[true]);
+
+		sim.set_input(0, true).unwrap();
+		sim.step(5).unwrap();
+		assert_eq!(sim.outputs(), &[false]);
+
+		assert!(sim.set_input(1, true).is_err());
+	}
+
+	#[test]
+	fn simulator_records_probes() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Wire(Direction::Right);
+		world.set_probe(1, 0, "in".to_string());
+		let mut worlds = Worlds::default();
+		let wid = worlds.push(world);
+
+		let mut sim = Simulator::new(worlds).unwrap();
+		sim.set_main(wid).unwrap();
+		sim.step(3).unwrap();
+		sim.set_input(0, true).unwrap();
+		sim.step(3).unwrap();
+
+		let trace = sim.game().trace.as_ref().unwrap();
+		assert_eq!(trace.end() - trace.start(), 6);
+		// the wire lights up the tick after the input does
+		assert_eq!(
+			trace.changes(0).collect::<Vec<_>>(),
+			[(0, false), (4, true)]
+		);
+	}
+}
@@ -0,0 +1,282 @@
+//! recording the probes of a world (see [World::probes]) every tick,
+//! and writing the recording as a value change dump that gtkwave and friends can open
+
+use std::{
+	collections::VecDeque,
+	io::{self, Write},
+	ops::Range,
+};
+
+use crate::{game::WorldId, world::World};
+
+/// how many of the latest ticks a trace keeps, older ones get forgotten as new ones are recorded
+pub const MAX_TICKS: u64 = 1 << 16;
+
+/// the on/off state of every probe of a world, tick by tick, for the last [MAX_TICKS] ticks \
+/// only changes are stored, so probes that don't do much don't take up much
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Trace {
+	world_id: WorldId,
+	/// coords and names of the probes, in the order of [World::probes]
+	probes: Vec<((i32, i32), String)>,
+	/// the first tick still kept
+	start: u64,
+	/// how many ticks are kept
+	len: u64,
+	/// (tick, new value) of every probe, in order \
+	/// once a tick has been recorded every probe has a change at `start`
+	changes: Vec<VecDeque<(u64, bool)>>,
+}
+impl Trace {
+	/// an empty trace of `world`'s probes, the first tick recorded should be `start`
+	pub fn new(world_id: WorldId, world: &World, start: u64) -> Self {
+		let probes_len = world.probes().len();
+		Self {
+			world_id,
+			probes: world
+				.probes()
+				.into_iter()
+				.map(|(coords, name)| (coords, name.to_string()))
+				.collect(),
+			start,
+			len: 0,
+			changes: vec![VecDeque::new(); probes_len],
+		}
+	}
+	/// whether this trace is recording the same probes `world` has
+	pub fn matches(&self, world_id: WorldId, world: &World) -> bool {
+		let probes = world.probes();
+		self.world_id == world_id
+			&& self.probes.len() == probes.len()
+			&& self
+				.probes
+				.iter()
+				.zip(probes)
+				.all(|((a_coords, a_name), (b_coords, b_name))| {
+					*a_coords == b_coords && a_name == b_name
+				})
+	}
+
+	/// records the next tick, `is_on` gets the coords of every probe \
+	/// forgets the oldest tick if there's already [MAX_TICKS] of them
+	pub fn record(&mut self, mut is_on: impl FnMut((i32, i32)) -> bool) {
+		if self.len >= MAX_TICKS {
+			self.forget_before(self.start + 1);
+		}
+		let tick = self.start + self.len;
+		for ((coords, _), changes) in self.probes.iter().zip(self.changes.iter_mut()) {
+			let on = is_on(*coords);
+			if changes.back().map(|(_, current)| *current) != Some(on) {
+				changes.push_back((tick, on));
+			}
+		}
+		self.len += 1;
+	}
+	/// moves the start of the trace up to `start`, what the probes were at then becoming their first change
+	fn forget_before(&mut self, start: u64) {
+		for changes in self.changes.iter_mut() {
+			while changes.get(1).is_some_and(|(tick, _)| *tick <= start) {
+				changes.pop_front();
+			}
+			if let Some((tick, _)) = changes.front_mut() {
+				*tick = (*tick).max(start);
+			}
+		}
+		self.len -= start - self.start;
+		self.start = start;
+	}
+
+	pub fn world_id(&self) -> WorldId {
+		self.world_id
+	}
+	pub fn probes(&self) -> &[((i32, i32), String)] {
+		&self.probes
+	}
+	/// the first recorded tick
+	pub fn start(&self) -> u64 {
+		self.start
+	}
+	/// the tick after the last recorded one
+	pub fn end(&self) -> u64 {
+		self.start + self.len
+	}
+	/// (tick, new value) every time the probe changed
+	pub fn changes(&self, probe: usize) -> impl Iterator<Item = (u64, bool)> + '_ {
+		self.changes.get(probe).into_iter().flatten().copied()
+	}
+	/// the value of the probe at every tick in `ticks`, ticks that weren't recorded are off
+	pub fn values(&self, probe: usize, ticks: Range<u64>) -> Vec<bool> {
+		let mut values = Vec::with_capacity(ticks.clone().count());
+		let empty = VecDeque::new();
+		let all = self.changes.get(probe).unwrap_or(&empty);
+		// the last change before the window decides what it starts as, so history before it gets skipped over
+		let first = all.partition_point(|(tick, _)| *tick <= ticks.start);
+		let mut on = first.checked_sub(1).is_some_and(|i| all[i].1);
+		let mut changes = all.range(first..).copied().peekable();
+		for tick in ticks {
+			while let Some((_, change)) = changes.next_if(|(at, _)| *at <= tick) {
+				on = change;
+			}
+			values.push(on && tick >= self.start && tick < self.end());
+		}
+		values
+	}
+
+	/// forgets every tick from `end` on, so they can be recorded again
+	pub fn truncate(&mut self, end: u64) {
+		if end >= self.end() {
+			return;
+		}
+		self.len = end.saturating_sub(self.start);
+		for changes in self.changes.iter_mut() {
+			changes.truncate(changes.partition_point(|(tick, _)| *tick < end));
+		}
+	}
+
+	/// writes the trace as a value change dump, one tick is one nanosecond
+	pub fn write_vcd(&self, w: &mut impl Write) -> io::Result<()> {
+		writeln!(w, "$version signals $end")?;
+		writeln!(w, "$timescale 1ns $end")?;
+		writeln!(w, "$scope module world_{} $end", self.world_id)?;
+		for (i, (_, name)) in self.probes.iter().enumerate() {
+			writeln!(w, "$var wire 1 {} {} $end", vcd_id(i), vcd_name(name))?;
+		}
+		writeln!(w, "$upscope $end")?;
+		writeln!(w, "$enddefinitions $end")?;
+
+		// (tick, probe index, new value), going through the ticks in order
+		let mut changes = self
+			.changes
+			.iter()
+			.enumerate()
+			.flat_map(|(i, changes)| changes.iter().map(move |(tick, on)| (*tick, i, *on)))
+			.collect::<Vec<_>>();
+		changes.sort_unstable();
+
+		let mut prev_tick = None;
+		for (tick, i, on) in changes {
+			if prev_tick != Some(tick) {
+				writeln!(w, "#{}", tick - self.start)?;
+				prev_tick = Some(tick);
+			}
+			writeln!(w, "{}{}", if on { 1 } else { 0 }, vcd_id(i))?;
+		}
+		// so the last values show up as lasting until the end
+		if self.len > 0 && prev_tick != Some(self.end() - 1) {
+			writeln!(w, "#{}", self.len - 1)?;
+		}
+		Ok(())
+	}
+	pub fn to_vcd(&self) -> String {
+		let mut vcd = vec![];
+		self.write_vcd(&mut vcd)
+			.expect("writing to a vec doesn't fail");
+		String::from_utf8(vcd).expect("the vcd is made of utf-8 strings")
+	}
+}
+
+/// vcd identifiers are made up of the printable ascii characters
+fn vcd_id(mut i: usize) -> String {
+	const FIRST: u8 = b'!';
+	const COUNT: usize = (b'~' - b'!' + 1) as usize;
+
+	let mut id = String::new();
+	loop {
+		id.push((FIRST + (i % COUNT) as u8) as char);
+		i /= COUNT;
+		if i == 0 {
+			return id;
+		}
+		i -= 1;
+	}
+}
+/// vcd names can't have whitespace in them
+fn vcd_name(name: &str) -> String {
+	let name = name.split_whitespace().collect::<Vec<_>>().join("_");
+	if name.is_empty() {
+		"probe".to_string()
+	} else {
+		name
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vcd_only_has_changes() {
+		let mut world = World::default();
+		world.set_probe(0, 0, "clk".to_string());
+		world.set_probe(1, 0, "data in".to_string());
+
+		let mut trace = Trace::new(WorldId::default(), &world, 10);
+		for (clk, data) in [(false, true), (true, true), (false, true), (false, true)] {
+			trace.record(|coords| if coords == (0, 0) { clk } else { data });
+		}
+		assert_eq!(trace.end(), 14);
+		assert_eq!(
+			trace.changes(0).collect::<Vec<_>>(),
+			[(10, false), (11, true), (12, false)]
+		);
+		assert_eq!(
+			trace.values(0, 9..15),
+			[false, false, true, false, false, false]
+		);
+		assert_eq!(
+			trace.values(1, 9..15),
+			[false, true, true, true, true, false]
+		);
+
+		let vcd = trace.to_vcd();
+		assert!(vcd.contains("$var wire 1 ! clk $end\n"));
+		assert!(vcd.contains("$var wire 1 \" data_in $end\n"));
+		assert!(vcd.ends_with("$enddefinitions $end\n#0\n0!\n1\"\n#1\n1!\n#2\n0!\n#3\n"));
+	}
+
+	#[test]
+	fn forgets_the_oldest_ticks() {
+		let mut world = World::default();
+		world.set_probe(0, 0, "slow".to_string());
+		world.set_probe(1, 0, "never".to_string());
+		let on_at = |tick: u64| tick / 3 % 2 == 1;
+
+		let mut trace = Trace::new(WorldId::default(), &world, 0);
+		let ticks = MAX_TICKS + 10;
+		for tick in 0..ticks {
+			trace.record(|coords| coords == (0, 0) && on_at(tick));
+		}
+		let index = |at: (i32, i32)| {
+			trace
+				.probes()
+				.iter()
+				.position(|(coords, _)| *coords == at)
+				.unwrap()
+		};
+		let (slow, never) = (index((0, 0)), index((1, 0)));
+		assert_eq!(trace.start(), 10);
+		assert_eq!(trace.end(), ticks);
+		assert_eq!(trace.changes(slow).next(), Some((10, true)));
+		assert_eq!(trace.changes(never).collect::<Vec<_>>(), [(10, false)]);
+
+		let window = ticks - 20..ticks + 2;
+		let expected = window
+			.clone()
+			.map(|tick| tick < ticks && on_at(tick))
+			.collect::<Vec<_>>();
+		assert_eq!(trace.values(slow, window), expected);
+		assert_eq!(trace.values(slow, 8..13), [false, false, true, true, false]);
+
+		trace.truncate(ticks - 5);
+		assert_eq!(trace.end(), ticks - 5);
+		assert!(trace.changes(slow).all(|(tick, _)| tick < ticks - 5));
+	}
+
+	#[test]
+	fn vcd_ids_dont_repeat() {
+		let ids = (0..10000)
+			.map(vcd_id)
+			.collect::<std::collections::HashSet<_>>();
+		assert_eq!(ids.len(), 10000);
+	}
+}
@@ -0,0 +1,498 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{game::WorldId, world::*};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Error)]
+pub enum BlockError {
+	#[error("recursive\n{inst_id}|{id}")]
+	Recursion { inst_id: usize, id: usize },
+	#[error("world didn't exist\n{}\n{inst_id}|{id}", wid.short())]
+	WorldDoesntExist {
+		wid: WorldId,
+		inst_id: usize,
+		id: usize,
+	},
+	#[error("id too big\n{}\n{this_was} > {max_id}", wid.short())]
+	MaxIdExceeded {
+		wid: WorldId,
+		max_id: usize,
+		this_was: usize,
+	},
+	#[error("unknown error")]
+	Other,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub enum Block {
+	#[default]
+	Nothing,
+	Wire(Direction),
+	Router, // for now, two routers next to each are gonna power each other indefinitely
+	Junction,
+	Switch(bool), // true if powered
+	Not(bool),
+	Input(usize),
+	Output(usize),
+	Foreign(WorldId, usize, usize), // (world_id (for redundancy), inst_id, input_and_output_id)
+	Error(BlockError),
+	// new variants go at the end so saves made before them keep loading
+	/// (facing, ticks, held) \
+	/// passes signals in its facing direction, taking as many ticks as `ticks` wires would \
+	/// held is the signals currently inside as a bitmask, the lowest bit gets passed on next tick
+	Delay(Direction, u8, u32),
+	/// (facing, received, stored) \
+	/// SR latch: set from the back, reset from either side, outputs the stored value to the front \
+	/// if set and reset arrive in the same tick, reset wins
+	Latch(Direction, Received, bool),
+	/// (facing, received, previous clock, stored) \
+	/// D flip-flop: data from the back, clock from either side, outputs the stored value to the front \
+	/// stores data when the clock goes from off to on
+	FlipFlop(Direction, Received, bool, bool),
+	/// (mode, pin) \
+	/// one pin of a display, pins are laid out two per row starting from the top left, see [DisplayMode::pin_offset] \
+	/// the display itself is rendered by pin 0 based on the drawmap
+	Display(DisplayMode, u8),
+	/// pulses in all directions every `period` ticks \
+	/// phase is how many ticks passed since the last pulse, a clock pulses when it's 0
+	Clock { period: u32, phase: u32 },
+	/// powered while pressed, or for a single tick after being pressed if `pulse` is set \
+	/// pressed is set by the interact tool in the main world's [BlockStates]
+	Button { pulse: bool, pressed: bool },
+	/// (channel) \
+	/// passes every signal it receives to the receivers on the same channel
+	Transmitter(u8),
+	/// (channel) \
+	/// powers every direction if a transmitter on the same channel was powered last tick
+	Receiver(u8),
+	/// (facing, width) \
+	/// like a wire, but carries `width` independent lines as a [Signal::Bus]
+	Bus(Direction, u8),
+	/// (facing, bit) \
+	/// passes a bus from the back through the front, and powers both sides if `bit` is on
+	Splitter(Direction, u8),
+	/// (facing, bit) \
+	/// passes a bus from the back through the front, turning `bit` on if either side is powered
+	Merger(Direction, u8),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum DisplayMode {
+	/// every pin lights one segment: a, b, c, d, e, f, g, decimal point
+	SevenSegment,
+	/// the 4 pins are a binary number (pin 0 is the lowest bit) displayed as a hex digit
+	Hex,
+}
+impl DisplayMode {
+	pub fn pins(self) -> u8 {
+		match self {
+			Self::SevenSegment => 8,
+			Self::Hex => 4,
+		}
+	}
+	/// relative coords of the given pin from pin 0
+	pub fn pin_offset(pin: u8) -> (i32, i32) {
+		((pin % 2) as i32, (pin / 2) as i32)
+	}
+	/// returns ((relative x, relative y), pin) for every pin of a display in this mode
+	pub fn footprint(self) -> impl Iterator<Item = ((i32, i32), u8)> {
+		(0..self.pins()).map(|pin| (Self::pin_offset(pin), pin))
+	}
+	/// returns which segments should be lit based on the pins that received a signal \
+	/// bit 0 is segment a, bit 7 is the decimal point
+	pub fn segments(self, pins: u8) -> u8 {
+		// a b c d e f g for every hex digit
+		const HEX_SEGMENTS: [u8; 16] = [
+			0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F, 0x77, 0x7C, 0x39, 0x5E,
+			0x79, 0x71,
+		];
+		match self {
+			Self::SevenSegment => pins,
+			Self::Hex => HEX_SEGMENTS[(pins & 0xF) as usize],
+		}
+	}
+}
+
+/// which sides of a [Block::Latch] or [Block::FlipFlop] received a signal this tick \
+/// sides are relative to the block's facing direction
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct Received {
+	pub back: bool,
+	pub side: bool,
+}
+impl Received {
+	pub const NONE: Self = Self {
+		back: false,
+		side: false,
+	};
+
+	/// returns self with the side `from` points to marked as received, if it's an input side
+	pub fn receive(self, facing: Direction, from: Option<Direction>) -> Self {
+		match from {
+			Some(from) if from == facing.reverse() => Self { back: true, ..self },
+			Some(from) if from == facing.rotate_l() || from == facing.rotate_r() => Self {
+				side: true,
+				..self
+			},
+			_ => self,
+		}
+	}
+}
+
+impl Block {
+	/// the maximum ticks a [Block::Delay] can be set to
+	pub const DELAY_MAX: u8 = 8;
+	/// how many channels there are for transmitters and receivers
+	pub const CHANNELS: u8 = 16;
+	/// the most lines a [Block::Bus] can carry, one per bit of [Signal::Bus]
+	pub const BUS_MAX_WIDTH: u8 = 32;
+
+	/// the bits a bus of the given width can carry
+	pub fn bus_mask(width: u8) -> u32 {
+		match width.min(Self::BUS_MAX_WIDTH) {
+			0 => 0,
+			width => u32::MAX >> (Self::BUS_MAX_WIDTH - width),
+		}
+	}
+
+	/// syntax: push_move(relative_x, relative_y, signal)
+	pub fn pass(
+		&self,
+		signal: Signal,
+		from: Option<Direction>,
+		mut push_move: impl FnMut(PushMoveTo, Signal),
+	) -> Option<Self> {
+		let mut all_directions = |signal: Signal| {
+			push_move(PushMoveTo::Rel(1, 0), signal.clone());
+			push_move(PushMoveTo::Rel(0, 1), signal.clone());
+			push_move(PushMoveTo::Rel(-1, 0), signal.clone());
+			push_move(PushMoveTo::Rel(0, -1), signal);
+		};
+		// splitters and mergers are the only way on and off a bus
+		if let (Signal::Bus(_), false) = (
+			&signal,
+			matches!(self, Self::Bus(..) | Self::Splitter(..) | Self::Merger(..)),
+		) {
+			return None;
+		}
+		match self {
+			Self::Wire(dir) => {
+				// only accept the signal if it's coming from any other direction than the wire is pointing to
+				if from.map(|from| from == *dir).unwrap_or(false) {
+				} else {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), signal);
+				}
+			}
+			Self::Router => {
+				all_directions(Default::default());
+			}
+			Self::Junction => {
+				if let Some(dir) = from {
+					let (rel_x, rel_y) = dir.reverse().rel();
+					push_move(PushMoveTo::Rel(rel_x, rel_y), signal)
+				}
+			}
+			Self::Not(_) => return Some(Self::Not(true)),
+			&Self::Delay(dir, ticks, held) => {
+				// same as wires, don't accept signals coming from the front
+				if from != Some(dir) {
+					let ticks = ticks.clamp(1, Self::DELAY_MAX);
+					return Some(Self::Delay(dir, ticks, held | 1 << (ticks - 1)));
+				}
+			}
+			&Self::Latch(dir, received, stored) => {
+				return Some(Self::Latch(dir, received.receive(dir, from), stored))
+			}
+			&Self::FlipFlop(dir, received, prev_clock, stored) => {
+				return Some(Self::FlipFlop(
+					dir,
+					received.receive(dir, from),
+					prev_clock,
+					stored,
+				))
+			}
+			Self::Switch(_) => {}
+			Self::Input(_) => {
+				match signal {
+					Signal::ExternalPoweron => {
+						all_directions(Signal::Default);
+					}
+					_ => (), // do nothing unless externally powered on
+				}
+			}
+			Self::Output(id) => push_move(PushMoveTo::OutputID(*id), signal),
+			Self::Transmitter(channel) => push_move(PushMoveTo::Channel(*channel), Signal::Default),
+			Self::Receiver(_) => {
+				// only powered by transmitters
+				if signal == Signal::ExternalPoweron {
+					all_directions(Signal::Default)
+				}
+			}
+			&Self::Bus(dir, width) => {
+				// same as wires, don't accept signals coming from the front
+				if let (Signal::Bus(bits), true) = (signal, from != Some(dir)) {
+					let bits = bits & Self::bus_mask(width);
+					if bits != 0 {
+						let (rx, ry) = dir.rel();
+						push_move(PushMoveTo::Rel(rx, ry), Signal::Bus(bits));
+					}
+				}
+			}
+			&Self::Splitter(dir, bit) => match signal {
+				Signal::Bus(bits) if from == Some(dir.reverse()) => {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Signal::Bus(bits));
+					if bits >> bit & 1 == 1 {
+						for side in [dir.rotate_l(), dir.rotate_r()] {
+							let (rx, ry) = side.rel();
+							push_move(PushMoveTo::Rel(rx, ry), Signal::Default);
+						}
+					}
+				}
+				_ => {}
+			},
+			&Self::Merger(dir, bit) => match signal {
+				Signal::Bus(bits) if from == Some(dir.reverse()) => {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Signal::Bus(bits));
+				}
+				Signal::Bus(_) => {}
+				_ if from == Some(dir.rotate_l()) || from == Some(dir.rotate_r()) => {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Signal::Bus(1 << bit));
+				}
+				_ => {}
+			},
+			Self::Foreign(_, inst_id, id) => match signal {
+				Signal::Default => {
+					push_move(
+						PushMoveTo::Foreign {
+							inst_id: *inst_id,
+							id: *id,
+						},
+						signal,
+					);
+				}
+				Signal::DefaultIf(f) => {
+					if f(*self) {
+						push_move(
+							PushMoveTo::Foreign {
+								inst_id: *inst_id,
+								id: *id,
+							},
+							signal,
+						);
+					}
+				}
+				Signal::ExternalPoweron => {
+					fn cause(block: Block) -> bool {
+						match block {
+							Block::Foreign(_, _, _) => false,
+							_ => true,
+						}
+					}
+					all_directions(Signal::DefaultIf(cause));
+				}
+				Signal::Bus(_) => {} // already dropped above
+			},
+			Self::Nothing
+			| Block::Error(_)
+			| Block::Display(..)
+			| Block::Clock { .. }
+			| Block::Button { .. } => {}
+		}
+		None
+	}
+	/// whether [Block::tick] can do anything for this kind of block \
+	/// blocks that don't tick are skipped by [World::tick] unless they get a move, so new blocks that tick have to be added here
+	pub fn ticks(&self) -> bool {
+		matches!(
+			self,
+			Self::Switch(_)
+				| Self::Not(_)
+				| Self::Delay(..)
+				| Self::Latch(..)
+				| Self::FlipFlop(..)
+				| Self::Clock { .. }
+				| Self::Button { .. }
+		)
+	}
+	pub fn tick(&self, mut push_move: impl FnMut(PushMoveTo, Signal)) -> Option<Self> {
+		let mut all_directions = || {
+			push_move(PushMoveTo::Rel(1, 0), Default::default());
+			push_move(PushMoveTo::Rel(0, 1), Default::default());
+			push_move(PushMoveTo::Rel(-1, 0), Default::default());
+			push_move(PushMoveTo::Rel(0, -1), Default::default());
+			None
+		};
+		match self {
+			Self::Switch(true) => all_directions(),
+			Self::Not(true) => Some(Self::Not(false)),
+			Self::Not(false) => all_directions(),
+			&Self::Delay(dir, ticks, held) if held != 0 => {
+				if held & 1 == 1 {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Default::default());
+				}
+				Some(Self::Delay(dir, ticks, held >> 1))
+			}
+			&Self::Latch(dir, received, stored) => {
+				let stored = match received {
+					Received { side: true, .. } => false,
+					Received { back: true, .. } => true,
+					_ => stored,
+				};
+				if stored {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Default::default());
+				}
+				Some(Self::Latch(dir, Default::default(), stored))
+			}
+			&Self::FlipFlop(dir, received, prev_clock, stored) => {
+				let stored = if received.side && !prev_clock {
+					received.back
+				} else {
+					stored
+				};
+				if stored {
+					let (rx, ry) = dir.rel();
+					push_move(PushMoveTo::Rel(rx, ry), Default::default());
+				}
+				Some(Self::FlipFlop(dir, Default::default(), received.side, stored))
+			}
+			&Self::Clock { period, phase } => {
+				if phase == 0 {
+					all_directions();
+				}
+				Some(Self::Clock {
+					period,
+					phase: (phase + 1) % period.max(1),
+				})
+			}
+			Self::Button {
+				pulse: true,
+				pressed: true,
+			} => {
+				all_directions();
+				Some(Self::Button {
+					pulse: true,
+					pressed: false,
+				})
+			}
+			Self::Button { pressed: true, .. } => all_directions(),
+			_ => None,
+		}
+	}
+
+	/// returns `state` if it's the same kind of block as self, self otherwise \
+	/// this is how per-instance state gets laid over the layout of a world, see [BlockStates]
+	pub fn with_state(self, state: Option<Block>) -> Block {
+		match (self, state) {
+			// only the held signals are state, facing and ticks come from the layout
+			(Self::Delay(dir, ticks, _), Some(Self::Delay(_, _, held))) => {
+				Self::Delay(dir, ticks, held)
+			}
+			(Self::Latch(dir, ..), Some(Self::Latch(_, received, stored))) => {
+				Self::Latch(dir, received, stored)
+			}
+			(Self::FlipFlop(dir, ..), Some(Self::FlipFlop(_, received, prev_clock, stored))) => {
+				Self::FlipFlop(dir, received, prev_clock, stored)
+			}
+			(Self::Clock { period, .. }, Some(Self::Clock { phase, .. })) => {
+				Self::Clock { period, phase }
+			}
+			(Self::Button { pulse, .. }, Some(Self::Button { pressed, .. })) => {
+				Self::Button { pulse, pressed }
+			}
+			(_, Some(state)) if std::mem::discriminant(&self) == std::mem::discriminant(&state) => {
+				state
+			}
+			_ => self,
+		}
+	}
+
+	pub fn interact(&mut self) {
+		match self {
+			Self::Switch(s) => *s = !*s,
+			Self::Delay(_, ticks, _) => *ticks = *ticks % Self::DELAY_MAX + 1,
+			Self::Transmitter(channel) | Self::Receiver(channel) => {
+				*channel = (*channel + 1) % Self::CHANNELS
+			}
+			Self::Bus(_, width) => {
+				*width = if *width >= Self::BUS_MAX_WIDTH {
+					1
+				} else {
+					*width * 2
+				}
+			}
+			Self::Splitter(_, bit) | Self::Merger(_, bit) => *bit = (*bit + 1) % Self::BUS_MAX_WIDTH,
+			_ => {}
+		}
+	}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Direction {
+	Right,
+	Bottom,
+	Left,
+	Top,
+}
+impl Direction {
+	/// clockwise
+	pub fn rotate_r(self) -> Self {
+		match self {
+			Direction::Right => Direction::Bottom,
+			Direction::Bottom => Direction::Left,
+			Direction::Left => Direction::Top,
+			Direction::Top => Direction::Right,
+		}
+	}
+	pub fn rotate_l(self) -> Self {
+		match self {
+			Direction::Right => Direction::Top,
+			Direction::Bottom => Direction::Right,
+			Direction::Left => Direction::Bottom,
+			Direction::Top => Direction::Left,
+		}
+	}
+	pub fn reverse(self) -> Self {
+		match self {
+			Direction::Right => Direction::Left,
+			Direction::Bottom => Direction::Top,
+			Direction::Left => Direction::Right,
+			Direction::Top => Direction::Bottom,
+		}
+	}
+
+	pub fn is_axis_same(&self, b: &Self) -> bool {
+		b == self || b == &self.reverse()
+	}
+
+	pub fn rel(self) -> (i32, i32) {
+		self.rel_mul(1)
+	}
+	pub fn from_rel(rel: (i32, i32)) -> Option<Self> {
+		match rel {
+			(1, 0) => Some(Self::Right),
+			(0, 1) => Some(Self::Bottom),
+			(-1, 0) => Some(Self::Left),
+			(0, -1) => Some(Self::Top),
+			_ => None,
+		}
+	}
+
+	pub fn rel_mul(self, mul: i32) -> (i32, i32) {
+		match self {
+			Self::Right => (mul, 0),
+			Self::Bottom => (0, mul),
+			Self::Left => (-mul, 0),
+			Self::Top => (0, -mul),
+		}
+	}
+
+	pub fn all() -> impl Iterator<Item = Self> {
+		[Self::Right, Self::Bottom, Self::Left, Self::Top].into_iter()
+	}
+}
@@ -84,16 +84,4 @@ impl Chunk<Block> {
 
 		chunk
 	}
-	pub fn tick(&mut self, mut push_move: impl FnMut(PushMoveTo, (i32, i32), Signal)) {
-		let old_self = *self;
-		for x in 0..CHUNK_SIZE as i32 {
-			for y in 0..CHUNK_SIZE as i32 {
-				let a = crate::continue_on_none!(old_self.at(x, y));
-
-				if let Some(b) = a.tick(|a, b| push_move(a, (x, y), b)) {
-					*crate::continue_on_none!(self.mut_at(x, y)) = b;
-				}
-			}
-		}
-	}
 }
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::world::*;
+
+/// (coords, before, after) of an annotation or probe, none where there wasn't one
+type TextChange = ((i32, i32), Option<String>, Option<String>);
+
+/// a change to a world that can be undone and redone, see [World::start_edit] \
+/// every list holds (coords, before, after)
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Edit {
+	blocks: Vec<((i32, i32), Block, Block)>,
+	annotations: Vec<TextChange>,
+	probes: Vec<TextChange>,
+	/// (before, after), none if they didn't change
+	pins: Option<(Pins, Pins)>,
+}
+
+/// what a world was like before the edit being recorded \
+/// blocks are only saved once they're about to change, the rest is small enough to copy up front
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Journal<B> {
+	pub blocks: HashMap<(i32, i32), B>,
+	annotations: HashMap<(i32, i32), String>,
+	probes: HashMap<(i32, i32), String>,
+	pins: Pins,
+}
+impl<B> Journal<B> {
+	pub fn of(world: &World<B>) -> Self {
+		Self {
+			blocks: HashMap::new(),
+			annotations: world
+				.annotations()
+				.map(|(coords, text)| (coords, text.to_string()))
+				.collect(),
+			probes: world
+				.probes()
+				.into_iter()
+				.map(|(coords, name)| (coords, name.to_string()))
+				.collect(),
+			pins: world.pins().clone(),
+		}
+	}
+}
+
+impl Edit {
+	/// what changed between the journal and the world as it is now
+	pub(super) fn since(journal: Journal<Block>, world: &World) -> Self {
+		let mut blocks = journal
+			.blocks
+			.into_iter()
+			.filter_map(|(coords, before)| {
+				let after = world.at(coords.0, coords.1).copied().unwrap_or_default();
+				(before != after).then_some((coords, before, after))
+			})
+			.collect::<Vec<_>>();
+		blocks.sort_by_key(|(coords, _, _)| *coords);
+
+		let after = Journal::of(world);
+		let annotations = changed(&journal.annotations, &after.annotations);
+		let probes = changed(&journal.probes, &after.probes);
+		let pins = (journal.pins != after.pins).then_some((journal.pins, after.pins));
+
+		Self {
+			blocks,
+			annotations,
+			probes,
+			pins,
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.blocks.is_empty()
+			&& self.annotations.is_empty()
+			&& self.probes.is_empty()
+			&& self.pins.is_none()
+	}
+	/// whether an input, output or foreign got placed or removed, meaning the moves have to be regenerated
+	pub fn changes_io(&self) -> bool {
+		self.blocks.iter().any(|(_, before, after)| {
+			[before, after]
+				.into_iter()
+				.any(|b| matches!(b, Block::Input(_) | Block::Output(_) | Block::Foreign(..)))
+		})
+	}
+
+	/// forgets the state of every block the edit changed, so a block placed where another one was starts out
+	/// cleared instead of picking up what the old one held, see [BlockStates]
+	pub fn clear_states(&self, state: &mut BlockStates) {
+		for (coords, _, _) in self.blocks.iter() {
+			if let Some(Some(_)) = state.at(coords.0, coords.1) {
+				*state.mut_at(coords.0, coords.1) = None;
+			}
+		}
+	}
+
+	/// puts the world back the way it was before (`undo`) or after the edit
+	pub(super) fn apply(&self, world: &mut World, undo: bool) {
+		fn pick<T>(undo: bool, before: T, after: T) -> T {
+			if undo {
+				before
+			} else {
+				after
+			}
+		}
+
+		for &(coords, before, after) in self.blocks.iter() {
+			*world.mut_at(coords.0, coords.1) = pick(undo, before, after);
+		}
+		for (coords, before, after) in self.annotations.iter() {
+			let text = pick(undo, before, after).clone().unwrap_or_default();
+			world.set_annotation(coords.0, coords.1, text);
+		}
+		for (coords, before, after) in self.probes.iter() {
+			match pick(undo, before, after) {
+				Some(name) => world.set_probe(coords.0, coords.1, name.clone()),
+				None => {
+					world.remove_probe(coords.0, coords.1);
+				}
+			}
+		}
+		if let Some((before, after)) = &self.pins {
+			*world.pins_mut() = pick(undo, before, after).clone();
+		}
+	}
+}
+
+/// (coords, before, after) of every entry that's different between the two
+fn changed(
+	before: &HashMap<(i32, i32), String>,
+	after: &HashMap<(i32, i32), String>,
+) -> Vec<TextChange> {
+	let mut changed = before
+		.keys()
+		.chain(after.keys())
+		.filter(|coords| before.get(coords) != after.get(coords))
+		.map(|coords| (*coords, before.get(coords).cloned(), after.get(coords).cloned()))
+		.collect::<Vec<_>>();
+	changed.sort_by_key(|(coords, _, _)| *coords);
+	changed.dedup_by_key(|(coords, _, _)| *coords);
+	changed
+}
@@ -0,0 +1,183 @@
+use std::{
+	collections::{HashMap, HashSet},
+	hash::Hash,
+	sync::{PoisonError, RwLock},
+};
+
+use crate::{game::WorldId, world::*};
+
+/// lookups of a world's blocks by what they are, so finding an input doesn't mean going over every block \
+/// built from the blocks on the first lookup, after that only the coords that changed get reindexed (on the next lookup) \
+/// ignored when comparing worlds, so worlds with the same blocks are equal whether they've built it or not
+#[derive(Debug, Default)]
+pub(super) struct Index(RwLock<IndexState>);
+
+#[derive(Clone, Debug, Default)]
+struct IndexState {
+	/// none until the first lookup, or after a change the index can't follow coord by coord
+	built: Option<Indexes>,
+	/// coords that changed since the last lookup
+	dirty: HashSet<(i32, i32)>,
+}
+
+/// id -> coords, for the foreigns of one instance
+pub(super) type ForeignsById = HashMap<usize, Vec<((i32, i32), WorldId)>>;
+
+/// every list is a vec since nothing stops two blocks from having the same id
+#[derive(Clone, Debug, Default)]
+pub(super) struct Indexes {
+	/// what every indexed coord is indexed as, so it can be taken out again when its block changes
+	keys: HashMap<(i32, i32), Key>,
+	pub inputs: HashMap<usize, Vec<(i32, i32)>>,
+	pub outputs: HashMap<usize, Vec<(i32, i32)>>,
+	/// inst_id -> id -> coords
+	pub foreigns: HashMap<usize, ForeignsById>,
+	pub transmitters: HashMap<u8, Vec<(i32, i32)>>,
+	pub receivers: HashMap<u8, Vec<(i32, i32)>>,
+	/// every block that does something every tick on its own, see [Block::ticks]
+	pub tickers: HashSet<(i32, i32)>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Key {
+	Input(usize),
+	Output(usize),
+	Foreign(WorldId, usize, usize),
+	Transmitter(u8),
+	Receiver(u8),
+	Ticker,
+}
+impl Key {
+	fn of(block: Block) -> Option<Self> {
+		match block {
+			Block::Input(id) => Some(Self::Input(id)),
+			Block::Output(id) => Some(Self::Output(id)),
+			Block::Foreign(wid, inst_id, id) => Some(Self::Foreign(wid, inst_id, id)),
+			Block::Transmitter(channel) => Some(Self::Transmitter(channel)),
+			Block::Receiver(channel) => Some(Self::Receiver(channel)),
+			b if b.ticks() => Some(Self::Ticker),
+			_ => None,
+		}
+	}
+}
+
+impl Indexes {
+	fn build(world: &World) -> Self {
+		let mut indexes = Self::default();
+		for (coords, block) in world.blocks() {
+			indexes.insert(coords, *block);
+		}
+		indexes
+	}
+	fn insert(&mut self, coords: (i32, i32), block: Block) {
+		let key = match Key::of(block) {
+			Some(key) => key,
+			None => return,
+		};
+		match key {
+			Key::Input(id) => self.inputs.entry(id).or_default().push(coords),
+			Key::Output(id) => self.outputs.entry(id).or_default().push(coords),
+			Key::Foreign(wid, inst_id, id) => self
+				.foreigns
+				.entry(inst_id)
+				.or_default()
+				.entry(id)
+				.or_default()
+				.push((coords, wid)),
+			Key::Transmitter(channel) => self.transmitters.entry(channel).or_default().push(coords),
+			Key::Receiver(channel) => self.receivers.entry(channel).or_default().push(coords),
+			Key::Ticker => {
+				self.tickers.insert(coords);
+			}
+		}
+		self.keys.insert(coords, key);
+	}
+	fn remove(&mut self, coords: (i32, i32)) {
+		fn remove_from<K: Hash + Eq, V>(
+			map: &mut HashMap<K, Vec<V>>,
+			key: K,
+			is_it: impl Fn(&V) -> bool,
+		) {
+			if let Some(list) = map.get_mut(&key) {
+				list.retain(|v| !is_it(v));
+				if list.is_empty() {
+					map.remove(&key);
+				}
+			}
+		}
+
+		match self.keys.remove(&coords) {
+			None => {}
+			Some(Key::Input(id)) => remove_from(&mut self.inputs, id, |c| *c == coords),
+			Some(Key::Output(id)) => remove_from(&mut self.outputs, id, |c| *c == coords),
+			Some(Key::Foreign(_, inst_id, id)) => {
+				if let Some(ids) = self.foreigns.get_mut(&inst_id) {
+					remove_from(ids, id, |(c, _)| *c == coords);
+					if ids.is_empty() {
+						self.foreigns.remove(&inst_id);
+					}
+				}
+			}
+			Some(Key::Transmitter(channel)) => {
+				remove_from(&mut self.transmitters, channel, |c| *c == coords)
+			}
+			Some(Key::Receiver(channel)) => {
+				remove_from(&mut self.receivers, channel, |c| *c == coords)
+			}
+			Some(Key::Ticker) => {
+				self.tickers.remove(&coords);
+			}
+		}
+	}
+}
+
+impl Index {
+	/// marks the coords as changed, nothing to do if the index hasn't been built yet
+	pub fn touch(&mut self, coords: (i32, i32)) {
+		let state = self.0.get_mut().unwrap_or_else(PoisonError::into_inner);
+		if state.built.is_some() {
+			state.dirty.insert(coords);
+		}
+	}
+	/// throws the whole index away, for changes that can't be followed coord by coord
+	pub fn invalidate(&mut self) {
+		*self.0.get_mut().unwrap_or_else(PoisonError::into_inner) = IndexState::default();
+	}
+	/// runs `f` on the up to date index of `world` \
+	/// `f` can't look anything up in the same world's index, that would deadlock
+	pub fn with<T>(&self, world: &World, f: impl FnOnce(&Indexes) -> T) -> T {
+		{
+			let state = self.0.read().unwrap_or_else(PoisonError::into_inner);
+			if let (Some(built), true) = (&state.built, state.dirty.is_empty()) {
+				return f(built);
+			}
+		}
+
+		let mut state = self.0.write().unwrap_or_else(PoisonError::into_inner);
+		let state = &mut *state;
+		let built = state.built.get_or_insert_with(|| Indexes::build(world));
+		for coords in state.dirty.drain() {
+			built.remove(coords);
+			if let Some(block) = world.at(coords.0, coords.1) {
+				built.insert(coords, *block);
+			}
+		}
+		f(built)
+	}
+}
+impl Clone for Index {
+	fn clone(&self) -> Self {
+		Self(RwLock::new(
+			self.0
+				.read()
+				.unwrap_or_else(PoisonError::into_inner)
+				.clone(),
+		))
+	}
+}
+impl PartialEq for Index {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+impl Eq for Index {}
@@ -8,6 +8,12 @@ macro_rules! module {
 module!(world);
 module!(chunk);
 module!(block);
+module!(pins);
+mod edit;
+mod index;
+pub use edit::Edit;
+use edit::Journal;
+use index::{Index, Indexes};
 
 #[macro_export]
 macro_rules! continue_on_none {
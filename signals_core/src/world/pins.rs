@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PinKind {
+	Input,
+	Output,
+}
+
+/// name, color and description of an input or output, shown on the foreigns that reference its world
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PinInfo {
+	pub name: String,
+	/// (r, g, b)
+	pub color: Option<(u8, u8, u8)>,
+	pub description: Option<String>,
+}
+
+/// metadata of a world's inputs and outputs, keyed by the id of their [super::Block::Input] or [super::Block::Output] \
+/// kept up to date with the ids by [super::World::io_blocks_fix]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Pins {
+	inputs: HashMap<usize, PinInfo>,
+	outputs: HashMap<usize, PinInfo>,
+}
+impl Pins {
+	fn of(&self, kind: PinKind) -> &HashMap<usize, PinInfo> {
+		match kind {
+			PinKind::Input => &self.inputs,
+			PinKind::Output => &self.outputs,
+		}
+	}
+	fn of_mut(&mut self, kind: PinKind) -> &mut HashMap<usize, PinInfo> {
+		match kind {
+			PinKind::Input => &mut self.inputs,
+			PinKind::Output => &mut self.outputs,
+		}
+	}
+
+	pub fn get(&self, kind: PinKind, id: usize) -> Option<&PinInfo> {
+		self.of(kind).get(&id)
+	}
+	/// setting the default info removes it
+	pub fn set(&mut self, kind: PinKind, id: usize, info: PinInfo) {
+		if info == PinInfo::default() {
+			self.of_mut(kind).remove(&id);
+		} else {
+			self.of_mut(kind).insert(id, info);
+		}
+	}
+	pub fn is_empty(&self) -> bool {
+		self.inputs.is_empty() && self.outputs.is_empty()
+	}
+
+	/// moves the info of every pin from its old id to its new id \
+	/// info of pins that aren't in `ids` is dropped, if an old id is there twice only the first one keeps it
+	pub fn renumber(&mut self, kind: PinKind, ids: impl Iterator<Item = (usize, usize)>) {
+		let mut old = std::mem::take(self.of_mut(kind));
+		let new = self.of_mut(kind);
+		for (old_id, new_id) in ids {
+			if let Some(info) = old.remove(&old_id) {
+				new.insert(new_id, info);
+			}
+		}
+	}
+}
@@ -0,0 +1,1023 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{drawtype::DrawType, game::WorldId, world::*};
+
+use rayon::prelude::*;
+use std::{collections::HashMap, hash::Hash};
+
+/// below this many signals and ticks a tick isn't worth splitting up between threads
+const PARALLEL_WORK: usize = 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Signal {
+	#[default]
+	Default,
+	ExternalPoweron,
+	DefaultIf(fn(Block) -> bool),
+	/// one bit per line of a bus, only [Block::Bus], [Block::Splitter] and [Block::Merger] accept these
+	Bus(u32),
+}
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+	Inside {
+		to: (i32, i32),
+		from: Option<Direction>,
+		signal: Signal,
+	},
+	/// inputs are placed into the moves vec by main, not world
+	Input { id: usize, signal: Signal },
+	/// outputs are placed in the vec by world but are supposed to be handled externally
+	Output { id: usize, signal: Signal },
+	/// foreigns
+	Foreign {
+		inst_id: usize,
+		id: usize,
+		signal: Signal,
+	},
+	/// powers every [Block::Receiver] on the channel
+	Channel { channel: u8, signal: Signal },
+}
+impl Move {
+	// wth i'm gonna have a new function
+	#[allow(dead_code)]
+	pub fn new(to: (i32, i32), from: Option<Direction>, signal: Signal) -> Self {
+		Self::Inside { to, from, signal }
+	}
+
+	pub fn signal(&self) -> &Signal {
+		match self {
+			Move::Inside { signal, .. } => signal,
+			Move::Input { signal, .. } => signal,
+			Move::Output { signal, .. } => signal,
+			Move::Foreign { signal, .. } => signal,
+			Move::Channel { signal, .. } => signal,
+		}
+	}
+}
+/// the to field of the push_move function
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PushMoveTo {
+	Rel(i32, i32),
+	OutputID(usize),
+	Foreign { inst_id: usize, id: usize },
+	Channel(u8),
+}
+
+/// per-instance state of a world's stateful blocks (like [Block::Not]), laid over the world's layout \
+/// this way multiple instances of the same world can be simulated without their states interfering
+pub type BlockStates = World<Option<Block>>;
+/// (coords, (world_id, inst_id, id)) of a foreign block
+pub type FoundForeign = ((i32, i32), (WorldId, usize, usize));
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct World<B = Block> {
+	chunks: HashMap<(i32, i32), Chunk<B>>,
+	/// free text notes attached to coordinates, they don't affect signals in any way \
+	/// saved after the worlds themselves so older saves keep loading, see [crate::game::saves]
+	#[serde(skip)]
+	annotations: HashMap<(i32, i32), String>,
+	/// saved the same way as annotations
+	#[serde(skip)]
+	pins: Pins,
+	/// names of the blocks whose signal gets recorded every tick, see [crate::trace::Trace] \
+	/// saved the same way as annotations
+	#[serde(skip)]
+	probes: HashMap<(i32, i32), String>,
+	/// where the inputs, outputs, foreigns and such are, only ever built for worlds of [Block]s
+	#[serde(skip)]
+	index: Index,
+	/// some while an edit is being recorded, see [World::start_edit]
+	#[serde(skip)]
+	journal: Option<Journal<B>>,
+	#[serde(skip)]
+	generation: Generation,
+}
+
+/// goes up every time a block might've changed, see [World::generation] \
+/// ignored when comparing worlds like the index is, two worlds with the same blocks are equal however they got there
+#[derive(Copy, Clone, Debug, Default)]
+struct Generation(u64);
+impl PartialEq for Generation {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+impl Eq for Generation {}
+
+impl<B> World<B> {
+	pub fn at(&self, x: i32, y: i32) -> Option<&B> {
+		let (chunk_coords, (block_x, block_y)) = world_coords_into_chunk_coords(x, y);
+		self.chunks
+			.get(&chunk_coords)
+			.map(|chunk| chunk.at(block_x, block_y))
+			.flatten()
+	}
+	#[allow(unused)]
+	pub fn set_chunk(&mut self, coords: (i32, i32), chunk: Chunk<B>) -> Option<Chunk<B>> {
+		self.index.invalidate();
+		self.generation.0 += 1;
+		self.chunks.insert(coords, chunk)
+	}
+	pub fn chunk(&self, coords: (i32, i32)) -> Option<&Chunk<B>> {
+		self.chunks.get(&coords)
+	}
+	/// changes whenever the blocks might've, so the same generation means the same blocks without looking at them \
+	/// only comparable between generations of the same world, and not kept when saving
+	pub fn generation(&self) -> u64 {
+		self.generation.0
+	}
+
+	pub fn annotation(&self, x: i32, y: i32) -> Option<&str> {
+		self.annotations.get(&(x, y)).map(|text| text.as_str())
+	}
+	/// an empty (or whitespace only) text removes the annotation
+	pub fn set_annotation(&mut self, x: i32, y: i32, text: String) {
+		if text.trim().is_empty() {
+			self.annotations.remove(&(x, y));
+		} else {
+			self.annotations.insert((x, y), text);
+		}
+	}
+	/// swaps the annotations of two coordinates, used to keep annotations on blocks that get moved
+	pub fn swap_annotations(&mut self, a: (i32, i32), b: (i32, i32)) {
+		let a_text = self.annotations.remove(&a);
+		let b_text = self.annotations.remove(&b);
+		if let Some(text) = a_text {
+			self.annotations.insert(b, text);
+		}
+		if let Some(text) = b_text {
+			self.annotations.insert(a, text);
+		}
+	}
+	pub fn probe(&self, x: i32, y: i32) -> Option<&str> {
+		self.probes.get(&(x, y)).map(|name| name.as_str())
+	}
+	pub fn set_probe(&mut self, x: i32, y: i32, name: String) {
+		self.probes.insert((x, y), name);
+	}
+	pub fn remove_probe(&mut self, x: i32, y: i32) -> Option<String> {
+		self.probes.remove(&(x, y))
+	}
+	/// same as [World::swap_annotations], but for probes
+	pub fn swap_probes(&mut self, a: (i32, i32), b: (i32, i32)) {
+		let a_name = self.probes.remove(&a);
+		let b_name = self.probes.remove(&b);
+		if let Some(name) = a_name {
+			self.probes.insert(b, name);
+		}
+		if let Some(name) = b_name {
+			self.probes.insert(a, name);
+		}
+	}
+	/// sorted by name, then by coords
+	pub fn probes(&self) -> Vec<((i32, i32), &str)> {
+		let mut probes = self
+			.probes
+			.iter()
+			.map(|(coords, name)| (*coords, name.as_str()))
+			.collect::<Vec<_>>();
+		probes.sort_by(|(a_coords, a_name), (b_coords, b_name)| {
+			a_name.cmp(b_name).then(a_coords.cmp(b_coords))
+		});
+		probes
+	}
+
+	pub fn pins(&self) -> &Pins {
+		&self.pins
+	}
+	pub fn pins_mut(&mut self) -> &mut Pins {
+		&mut self.pins
+	}
+	pub fn annotations(&self) -> impl Iterator<Item = ((i32, i32), &str)> {
+		self.annotations
+			.iter()
+			.map(|(coords, text)| (*coords, text.as_str()))
+	}
+
+	/// starts recording every change to the world's blocks, annotations, probes and pins, until [World::finish_edit] \
+	/// does nothing if it's already recording
+	pub fn start_edit(&mut self) {
+		if self.journal.is_none() {
+			self.journal = Some(Journal::of(self));
+		}
+	}
+}
+impl<B: Default + Clone> World<B> {
+	/// saves the block as it was before the edit being recorded, if there is one
+	fn journal(&mut self, (x, y): (i32, i32)) {
+		let first_change = match &self.journal {
+			Some(journal) => !journal.blocks.contains_key(&(x, y)),
+			None => false,
+		};
+		if first_change {
+			let block = self.at(x, y).cloned().unwrap_or_default();
+			if let Some(journal) = &mut self.journal {
+				journal.blocks.insert((x, y), block);
+			}
+		}
+	}
+	fn ensure(&mut self, chunk_coords: (i32, i32)) {
+		if !self.chunks.contains_key(&chunk_coords) {
+			self.chunks.insert(chunk_coords, Chunk::default());
+		}
+	}
+	pub fn mut_at(&mut self, x: i32, y: i32) -> &mut B {
+		let (chunk_coords, (block_x, block_y)) = world_coords_into_chunk_coords(x, y);
+		self.ensure(chunk_coords);
+		self.index.touch((x, y));
+		self.journal((x, y));
+		self.generation.0 += 1;
+		self.chunks
+			.get_mut(&chunk_coords)
+			.unwrap_or_else(|| panic!("looks like World::ensure failed (world coords: {x} {y}, calculated chunk coords: {chunk_coords:?}, block coords: {block_x} {block_y})"))
+			.mut_at(block_x, block_y).unwrap_or_else(|| panic!("chunk doesn't have coordinates block: {block_x} {block_y} @ world: {x} {y}"))
+	}
+	pub fn chunks(&self) -> std::collections::hash_map::Iter<'_, (i32, i32), chunk::Chunk<B>> {
+		self.chunks.iter()
+	}
+	pub fn chunks_mut(
+		&mut self,
+	) -> std::collections::hash_map::IterMut<'_, (i32, i32), chunk::Chunk<B>> {
+		self.index.invalidate();
+		self.generation.0 += 1;
+		self.chunks.iter_mut()
+	}
+
+	/// returns: ((x, y) size in chunks, (x, y) chunk offset added to base coords to make world render the left upmost chunk at 0,0)
+	pub fn size_and_offset(&self) -> ((i32, i32), (i32, i32)) {
+		let ((x_smallest, x_biggest), (y_smallest, y_biggest)) = match self.biggest_smallest() {
+			None => return ((0, 0), (0, 0)),
+			Some(a) => a,
+		};
+
+		let x_offset = -x_smallest;
+		let y_offset = -y_smallest;
+
+		(
+			(x_biggest - x_smallest + 1, y_biggest - y_smallest + 1),
+			(x_offset, y_offset),
+		)
+	}
+	/// returns none if there are no chunks
+	pub fn biggest_smallest(&self) -> Option<((i32, i32), (i32, i32))> {
+		let (mut x_smallest, mut x_biggest) = (0, 0);
+		let (mut y_smallest, mut y_biggest) = (0, 0);
+
+		let mut did_anything = false;
+
+		for ((cx, cy), _) in self.chunks() {
+			did_anything = true;
+
+			x_smallest = x_smallest.min(*cx);
+			x_biggest = x_biggest.max(*cx);
+
+			y_smallest = y_smallest.min(*cy);
+			y_biggest = y_biggest.max(*cy);
+		}
+
+		if did_anything {
+			Some(((x_smallest, x_biggest), (y_smallest, y_biggest)))
+		} else {
+			None
+		}
+	}
+
+	/// returns an iterator over every block there is in the world
+	pub fn blocks(&self) -> impl Iterator<Item = ((i32, i32), &B)> {
+		self.chunks()
+			.map(|(cc, c)| {
+				c.blocks_with_coords()
+					.map(|(bc, b)| (chunk_coords_into_world_coords(*cc, bc), b))
+			})
+			.flatten()
+	}
+}
+impl<B: Default + Copy> World<B> {
+	pub fn map_at(&mut self, x: i32, y: i32, f: impl FnOnce(B) -> B) {
+		let (chunk_coords, (block_x, block_y)) = world_coords_into_chunk_coords(x, y);
+		self.ensure(chunk_coords);
+		self.index.touch((x, y));
+		self.journal((x, y));
+		self.generation.0 += 1;
+		self.chunks
+			.get_mut(&chunk_coords)
+			.map(|chunk| chunk.map_at(block_x, block_y, f));
+	}
+}
+impl<B: Default + PartialEq> World<B> {
+	#[allow(unused)]
+	pub fn is_block_at(&self, x: i32, y: i32) -> bool {
+		match self.at(x, y) {
+			None => false,
+			Some(a) if *a != B::default() => true,
+			_ => false,
+		}
+	}
+}
+impl World<Block> {
+	/// set_drawtype should handle junctions where for all axis sets it to previous_value || new_value \
+	/// just call [DrawType::apply_new] \
+	/// the world itself is never modified, block state is read from and written to `state` \
+	/// big ticks get split up by chunk and worked on in parallel, the result is the same as doing it on one thread
+	pub fn tick<D: FnMut(i32, i32, DrawType)>(
+		&self,
+		state: &mut BlockStates,
+		moves: Vec<Move>,
+		set_drawtype: D,
+	) -> Vec<Move> {
+		self.tick_split_from(state, moves, set_drawtype, PARALLEL_WORK)
+	}
+	/// [World::tick], but only splits the work up if there's at least `split_from` of it
+	fn tick_split_from<D: FnMut(i32, i32, DrawType)>(
+		&self,
+		state: &mut BlockStates,
+		moves: Vec<Move>,
+		mut set_drawtype: D,
+		split_from: usize,
+	) -> Vec<Move> {
+		let moves = moves.into_iter().filter_map(|mov| match mov.signal() {
+			Signal::DefaultIf(f) => match mov {
+				Move::Inside { to,  from, .. } => {
+					if self.at(to.0, to.1).map(|b| f(*b)).unwrap_or(false) {
+						Some(Move::Inside { to, from, signal: Signal::Default })
+					} else {
+						None
+					}
+				}
+				_ => {
+					eprintln!("dropping a Signal::DefaultIf, since it only works with Move::Inside for performance");
+					None
+				}
+			},
+			_ => Some(mov)
+		});
+
+		// everything that happens this tick, in the order it'd happen if it was done one by one
+		let mut work = Vec::new();
+		for mov in moves {
+			match mov {
+				Move::Inside { to, from, signal } => work.push(Work::Inside { coords: to, from, signal }),
+				Move::Input { id, signal } => match self.find_input(id) {
+					Some(coords) => work.push(Work::Outside { coords, signal }),
+					None => eprintln!("didn't find an input with id {id}, dropping {signal:?}"),
+				},
+				Move::Foreign { inst_id, id, signal } => match self.find_foreign(inst_id, id) {
+					Some(coords) => work.push(Work::Outside { coords, signal }),
+					None => eprintln!("didn't find an foreign with inst_id {inst_id} and id {id}, dropping {signal:?}"),
+				},
+				Move::Channel { channel, .. } => {
+					for coords in self.find_receivers(channel) {
+						work.push(Work::Received { coords });
+					}
+				}
+				Move::Output { id, signal } => eprintln!("no Move::Output variant should be in the moves vec sent to world.tick, (Move::Output {{ id: {id}, signal: {signal:?} }})")
+			}
+		}
+		// only blocks that do something on their own get ticked, the rest only do anything when they get a move
+		let tickers = self.indexed(|i| i.tickers.iter().copied().collect::<Vec<_>>());
+		work.extend(tickers.into_iter().map(|coords| Work::Tick { coords }));
+
+		// every piece of work only touches the block it's at, so chunks don't depend on each other
+		let done = if work.len() < split_from {
+			vec![self.work(state, work.into_iter().enumerate())]
+		} else {
+			let mut by_chunk = HashMap::<(i32, i32), Vec<(usize, Work)>>::new();
+			for (i, w) in work.into_iter().enumerate() {
+				let (x, y) = w.coords();
+				by_chunk.entry(world_coords_into_chunk_coords(x, y).0).or_default().push((i, w));
+			}
+			let state = &*state;
+			by_chunk
+				.into_par_iter()
+				.map(|(_, work)| self.work(state, work.into_iter()))
+				.collect()
+		};
+
+		let mut new_moves = Vec::new();
+		let mut drawtypes = Vec::new();
+		for done in done {
+			new_moves.extend(done.moves);
+			drawtypes.extend(done.drawtypes);
+			for ((x, y), b) in done.states {
+				*state.mut_at(x, y) = Some(b);
+			}
+		}
+		// back in the order they'd be in if it was done one by one
+		new_moves.sort_unstable_by_key(|(i, _)| *i);
+		drawtypes.sort_unstable_by_key(|(i, _, _)| *i);
+
+		for (_, (x, y), dt) in drawtypes {
+			set_drawtype(x, y, dt);
+		}
+		let mut new_moves = new_moves.into_iter().flat_map(|(_, moves)| moves).collect::<Vec<_>>();
+		new_moves.dedup();
+		new_moves
+	}
+	/// does the work in the order it's given in \
+	/// the new block states aren't written to `state`, they're returned so multiple threads can do this at once
+	fn work(&self, state: &BlockStates, work: impl Iterator<Item = (usize, Work)>) -> Done {
+		let mut done = Done::default();
+		let mut new_moves = Vec::new();
+		macro_rules! gen_push_move {
+			($x:expr, $y:expr) => {
+				|to, signal| match to {
+					PushMoveTo::Rel(rx, ry) => {
+						let to = ($x + rx, $y + ry);
+						new_moves.push(Move::Inside {
+							to,
+							from: Direction::from_rel((rx, ry)).map(|dir| dir.reverse()),
+							signal,
+						});
+					}
+					PushMoveTo::OutputID(id) => new_moves.push(Move::Output { id, signal }),
+					PushMoveTo::Foreign { inst_id, id } => new_moves.push(Move::Foreign {
+						inst_id,
+						id,
+						signal,
+					}),
+					PushMoveTo::Channel(channel) => new_moves.push(Move::Channel { channel, signal }),
+				}
+			};
+		}
+
+		for (i, w) in work {
+			let (x, y) = w.coords();
+			let block_state = match done.states.get(&(x, y)) {
+				Some(b) => Some(*b),
+				None => state.at(x, y).copied().flatten(),
+			};
+			let a = crate::continue_on_none!(self.at(x, y)).with_state(block_state);
+
+			let new_block = match w {
+				Work::Inside { from, signal, .. } => {
+					// if a is a wire receiving a signal from the direction it's passing signals
+					let dt = match a {
+						Block::Wire(dir) | Block::Delay(dir, _, _) | Block::Bus(dir, _) => {
+							if !from.map(|from| from == dir).unwrap_or(false) {
+								Some(((x, y), DrawType::On))
+							} else {
+								None
+							}
+						}
+						Block::Junction => from.map(|dir| match dir {
+							Direction::Bottom | Direction::Top => ((x, y), DrawType::Junction { vertical: true, horizontal: false }),
+							Direction::Right | Direction::Left => ((x, y), DrawType::Junction { vertical: false, horizontal: true }),
+						}),
+						Block::Display(_, pin) => {
+							// the whole display is rendered by pin 0, so that's where the pins are collected
+							let (rx, ry) = DisplayMode::pin_offset(pin);
+							Some(((x - rx, y - ry), DrawType::Display { pins: 1 << pin }))
+						}
+						_ => Some(((x, y), DrawType::On)),
+					};
+					if let Some((coords, dt)) = dt {
+						done.drawtypes.push((i, coords, dt));
+					}
+
+					a.pass(signal, from, gen_push_move!(x, y))
+				}
+				Work::Outside { signal, .. } => a.pass(signal, None, gen_push_move!(x, y)),
+				Work::Received { .. } => {
+					done.drawtypes.push((i, (x, y), DrawType::On));
+					a.pass(Signal::ExternalPoweron, None, gen_push_move!(x, y))
+				}
+				Work::Tick { .. } => a.tick(gen_push_move!(x, y)),
+			};
+
+			if let Some(b) = new_block {
+				done.states.insert((x, y), b);
+			}
+			if !new_moves.is_empty() {
+				done.moves.push((i, std::mem::take(&mut new_moves)));
+			}
+		}
+		done
+	}
+
+	/// stops recording, returning everything that changed since [World::start_edit] \
+	/// none if nothing did, or if it wasn't recording
+	pub fn finish_edit(&mut self) -> Option<Edit> {
+		let edit = Edit::since(self.journal.take()?, self);
+		(!edit.is_empty()).then_some(edit)
+	}
+	pub fn undo(&mut self, edit: &Edit) {
+		edit.apply(self, true);
+	}
+	pub fn redo(&mut self, edit: &Edit) {
+		edit.apply(self, false);
+	}
+
+	/// returns the block at the given coords with the state from `state` applied, see [Block::with_state]
+	pub fn at_with_state(&self, state: &BlockStates, x: i32, y: i32) -> Option<Block> {
+		let block = self.at(x, y)?;
+		Some(block.with_state(state.at(x, y).copied().flatten()))
+	}
+
+	fn indexed<T>(&self, f: impl FnOnce(&Indexes) -> T) -> T {
+		self.index.with(self, f)
+	}
+
+	/// returns worlds coords \
+	/// with duplicate ids this is whichever of them got indexed first
+	pub fn find_input(&self, id: usize) -> Option<(i32, i32)> {
+		self.indexed(|i| i.inputs.get(&id).and_then(|coords| coords.first()).copied())
+	}
+	pub fn find_foreign(&self, inst_id: usize, id: usize) -> Option<(i32, i32)> {
+		self.indexed(|i| {
+			i.foreigns
+				.get(&inst_id)
+				.and_then(|ids| ids.get(&id))
+				.and_then(|coords| coords.first())
+				.map(|(coords, _)| *coords)
+		})
+	}
+	pub fn find_foreigns(&self) -> impl Iterator<Item = FoundForeign> {
+		self.indexed(|i| {
+			i.foreigns
+				.iter()
+				.flat_map(|(inst_id, ids)| {
+					ids.iter().flat_map(move |(id, coords)| {
+						coords
+							.iter()
+							.map(move |(coords, wid)| (*coords, (*wid, *inst_id, *id)))
+					})
+				})
+				.collect::<Vec<_>>()
+		})
+		.into_iter()
+	}
+	/// the foreigns of one instance, sorted by id
+	pub fn find_instance(&self, inst_id: usize) -> Vec<FoundForeign> {
+		let mut foreigns = self.indexed(|i| {
+			i.foreigns
+				.get(&inst_id)
+				.into_iter()
+				.flatten()
+				.flat_map(|(id, coords)| {
+					coords
+						.iter()
+						.map(move |(coords, wid)| (*coords, (*wid, inst_id, *id)))
+				})
+				.collect::<Vec<_>>()
+		});
+		foreigns.sort_by_key(|(coords, (_, _, id))| (*id, *coords));
+		foreigns
+	}
+
+	pub fn find_receivers(&self, channel: u8) -> Vec<(i32, i32)> {
+		self.indexed(|i| i.receivers.get(&channel).cloned().unwrap_or_default())
+	}
+	pub fn find_transmitters(&self, channel: u8) -> impl Iterator<Item = (i32, i32)> {
+		self.indexed(|i| i.transmitters.get(&channel).cloned().unwrap_or_default())
+			.into_iter()
+	}
+
+	pub fn inputs_count(&self) -> usize {
+		self.indexed(|i| i.inputs.values().map(Vec::len).sum())
+	}
+	pub fn outputs_count(&self) -> usize {
+		self.indexed(|i| i.outputs.values().map(Vec::len).sum())
+	}
+	pub fn max_f_id(&self) -> usize {
+		self.inputs_count().max(self.outputs_count()) - 1
+	}
+
+	/// this makes sure input-output block's ids are in order
+	/// and there are no holes or duplicates\
+	/// the info in [World::pins] moves along with the ids \
+	/// returns (inputs.len + 1, outputs.len + 1)
+	pub fn io_blocks_fix(&mut self) -> (usize, usize) {
+		let (mut inputs, mut outputs) = self.indexed(|i| {
+			let io = |ids: &HashMap<usize, Vec<(i32, i32)>>| {
+				ids.iter()
+					.flat_map(|(id, coords)| coords.iter().map(move |coords| (*id, *coords)))
+					.collect::<Vec<_>>()
+			};
+			(io(&i.inputs), io(&i.outputs))
+		});
+
+		// duplicates are sorted by coords so they get the same ids every time
+		inputs.sort();
+		outputs.sort();
+
+		self.pins.renumber(
+			PinKind::Input,
+			inputs.iter().enumerate().map(|(i, (id, _))| (*id, i)),
+		);
+		self.pins.renumber(
+			PinKind::Output,
+			outputs.iter().enumerate().map(|(i, (id, _))| (*id, i)),
+		);
+
+		let mut in_i = 0;
+		let mut out_i = 0;
+
+		for (i, (id, (x, y))) in inputs.iter().copied().enumerate() {
+			if i != id {
+				match self.mut_at(x, y) {
+					Block::Input(live_id) => *live_id = i,
+					_ => eprintln!("this case is literally impossible"),
+				}
+			}
+			in_i = i;
+		}
+		for (i, (id, (x, y))) in outputs.iter().copied().enumerate() {
+			if i != id {
+				match self.mut_at(x, y) {
+					Block::Output(live_id) => *live_id = i,
+					_ => eprintln!("this case is literally impossible"),
+				}
+			}
+			out_i = i;
+		}
+
+		(in_i, out_i)
+	}
+
+	pub fn outputs(&self) -> impl Iterator<Item = (usize, (i32, i32))> {
+		self.indexed(|i| {
+			i.outputs
+				.iter()
+				.flat_map(|(id, coords)| coords.iter().map(move |coords| (*id, *coords)))
+				.collect::<Vec<_>>()
+		})
+		.into_iter()
+	}
+}
+/// one block getting a signal or ticking, see [World::tick]
+enum Work {
+	/// a signal from inside the world
+	Inside {
+		coords: (i32, i32),
+		from: Option<Direction>,
+		signal: Signal,
+	},
+	/// a signal from outside the world, through an input or a foreign
+	Outside { coords: (i32, i32), signal: Signal },
+	/// a receiver hearing its channel
+	Received { coords: (i32, i32) },
+	Tick { coords: (i32, i32) },
+}
+impl Work {
+	fn coords(&self) -> (i32, i32) {
+		match self {
+			Work::Inside { coords, .. }
+			| Work::Outside { coords, .. }
+			| Work::Received { coords }
+			| Work::Tick { coords } => *coords,
+		}
+	}
+}
+/// what [World::work] did, everything tagged with the index of the work it came from
+#[derive(Default)]
+struct Done {
+	moves: Vec<(usize, Vec<Move>)>,
+	drawtypes: Vec<(usize, (i32, i32), DrawType)>,
+	states: HashMap<(i32, i32), Block>,
+}
+
+impl<B: Hash> Hash for World<B> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		for (coords, c) in self.chunks.iter() {
+			coords.hash(state);
+			c.hash(state);
+		}
+	}
+}
+
+pub fn chunk_coords_into_world_coords(
+	(chunk_x, chunk_y): (i32, i32),
+	(block_x, block_y): (i32, i32),
+) -> (i32, i32) {
+	(
+		chunk_x * CHUNK_SIZE as i32 + block_x,
+		chunk_y * CHUNK_SIZE as i32 + block_y,
+	)
+}
+pub fn world_coords_into_chunk_coords(x: i32, y: i32) -> ((i32, i32), (i32, i32)) {
+	// this function is pure hell
+	// i spent so much fucking time figuring this out i'm sure there's a one size fits all solution
+	// for this but i didn't come up with it. here's the code(it works!) that calculates coords based on which
+	// quarter of the world you're in
+
+	if x >= 0 && y >= 0 {
+		let chunk = (x / CHUNK_SIZE as i32, y / CHUNK_SIZE as i32);
+		let block = (x % CHUNK_SIZE as i32, y % CHUNK_SIZE as i32);
+		return (chunk, block);
+	}
+	if x < 0 && y >= 0 {
+		let chunk = (x / CHUNK_SIZE as i32 - 1, y / CHUNK_SIZE as i32);
+		let block = (
+			(CHUNK_SIZE as i32 + (x % CHUNK_SIZE as i32)),
+			// .min(CHUNK_SIZE as i32 - 1)
+			// .max(0),
+			y % CHUNK_SIZE as i32,
+		);
+
+		if block.0 == CHUNK_SIZE as i32 {
+			return ((chunk.0 + 1, chunk.1), (0, block.1));
+		}
+		return (chunk, block);
+	}
+	if x >= 0 && y < 0 {
+		let chunk = (x / CHUNK_SIZE as i32, y / CHUNK_SIZE as i32 - 1);
+		let block = (
+			x % CHUNK_SIZE as i32,
+			(CHUNK_SIZE as i32 + (y % CHUNK_SIZE as i32)),
+		);
+
+		if block.1 == CHUNK_SIZE as i32 {
+			return ((chunk.0, chunk.1 + 1), (block.0, 0));
+		}
+		return (chunk, block);
+	}
+	if x < 0 && y < 0 {
+		let chunk = (x / CHUNK_SIZE as i32 - 1, y / CHUNK_SIZE as i32 - 1);
+		let block = (
+			(CHUNK_SIZE as i32 + (x % CHUNK_SIZE as i32)),
+			(CHUNK_SIZE as i32 + (y % CHUNK_SIZE as i32)),
+		);
+
+		if block.0 == CHUNK_SIZE as i32 && block.1 != CHUNK_SIZE as i32 {
+			return ((chunk.0 + 1, chunk.1), (0, block.1));
+		}
+		if block.1 == CHUNK_SIZE as i32 && block.0 != CHUNK_SIZE as i32 {
+			return ((chunk.0, chunk.1 + 1), (block.0, 0));
+		}
+		if block.0 == CHUNK_SIZE as i32 && block.1 == CHUNK_SIZE as i32 {
+			return ((chunk.0 + 1, chunk.1 + 1), (0, 0));
+		}
+		return (chunk, block);
+	}
+
+	todo!()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn block_states_dont_interfere() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Not(false);
+
+		let mut powered = BlockStates::default();
+		let mut unpowered = BlockStates::default();
+
+		let powered_moves = world.tick(
+			&mut powered,
+			vec![Move::new((0, 0), Some(Direction::Left), Signal::Default)],
+			|_, _, _| {},
+		);
+		let unpowered_moves = world.tick(&mut unpowered, vec![], |_, _, _| {});
+
+		assert_eq!(powered_moves.len(), 0);
+		assert_eq!(unpowered_moves.len(), 4);
+		assert_eq!(world.at(0, 0), Some(&Block::Not(false)));
+	}
+
+	#[test]
+	fn delay_holds_signal() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Delay(Direction::Right, 3, 0);
+		let mut state = BlockStates::default();
+
+		let mut moves = vec![Move::new((0, 0), Some(Direction::Left), Signal::Default)];
+		for _ in 0..2 {
+			moves = world.tick(&mut state, moves, |_, _, _| {});
+			assert_eq!(moves.len(), 0);
+		}
+		moves = world.tick(&mut state, moves, |_, _, _| {});
+		assert_eq!(
+			moves,
+			vec![Move::new((1, 0), Some(Direction::Left), Signal::Default)]
+		);
+	}
+
+	#[test]
+	fn flip_flop_stores_on_rising_edge() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::FlipFlop(Direction::Right, Received::NONE, false, false);
+		let mut state = BlockStates::default();
+
+		let data = Move::new((0, 0), Some(Direction::Left), Signal::Default);
+		let clock = Move::new((0, 0), Some(Direction::Top), Signal::Default);
+		let mut tick = |moves: Vec<Move>| world.tick(&mut state, moves, |_, _, _| {}).len();
+
+		assert_eq!(tick(vec![data.clone()]), 0); // no clock, nothing stored
+		assert_eq!(tick(vec![data.clone(), clock.clone()]), 1); // rising edge, stores data
+		assert_eq!(tick(vec![clock.clone()]), 1); // clock still on, no edge
+		assert_eq!(tick(vec![]), 1);
+		assert_eq!(tick(vec![clock]), 0); // rising edge without data
+	}
+
+	/// puts `block` at (0, 0) as an edit, forgetting the old block's state like [crate::game::Game::finish_edit] does
+	fn replace(world: &mut World, state: &mut BlockStates, block: Block) {
+		world.start_edit();
+		*world.mut_at(0, 0) = block;
+		world
+			.finish_edit()
+			.expect("the block changed")
+			.clear_states(state);
+	}
+
+	#[test]
+	fn removed_and_replaced_latch_starts_cleared() {
+		let latch = Block::Latch(Direction::Right, Received::NONE, false);
+		let mut world = World::default();
+		*world.mut_at(0, 0) = latch;
+		let mut state = BlockStates::default();
+
+		let set = Move::new((0, 0), Some(Direction::Left), Signal::Default);
+		assert_eq!(
+			world.tick(&mut state, vec![set], |_, _, _| {}),
+			vec![Move::new((1, 0), Some(Direction::Left), Signal::Default)]
+		);
+
+		replace(&mut world, &mut state, Block::Nothing);
+		world.tick(&mut state, vec![], |_, _, _| {});
+		replace(&mut world, &mut state, latch);
+		assert_eq!(world.tick(&mut state, vec![], |_, _, _| {}), vec![]);
+	}
+
+	#[test]
+	fn new_latches_and_flip_flops_start_cleared() {
+		let data = Move::new((0, 0), Some(Direction::Left), Signal::Default);
+		let clock = Move::new((0, 0), Some(Direction::Top), Signal::Default);
+		for (block, store, replacement) in [
+			(
+				Block::Latch(Direction::Right, Received::NONE, false),
+				vec![data.clone()],
+				Block::Latch(Direction::Bottom, Received::NONE, false),
+			),
+			(
+				Block::FlipFlop(Direction::Right, Received::NONE, false, false),
+				vec![data.clone(), clock.clone()],
+				Block::FlipFlop(Direction::Bottom, Received::NONE, false, false),
+			),
+		] {
+			let mut world = World::default();
+			*world.mut_at(0, 0) = block;
+			let mut state = BlockStates::default();
+			world.tick(&mut state, store, |_, _, _| {});
+			// stored bits last until something clears them
+			assert_eq!(world.tick(&mut state, vec![], |_, _, _| {}).len(), 1);
+
+			// a different one of the same kind in the same spot
+			replace(&mut world, &mut state, replacement);
+			assert_eq!(world.tick(&mut state, vec![], |_, _, _| {}), vec![]);
+		}
+	}
+
+	#[test]
+	fn bus_carries_bits_between_merger_and_splitter() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Merger(Direction::Right, 3);
+		*world.mut_at(1, 0) = Block::Bus(Direction::Right, 8);
+		*world.mut_at(2, 0) = Block::Splitter(Direction::Right, 3);
+		*world.mut_at(2, 1) = Block::Wire(Direction::Bottom);
+		let mut state = BlockStates::default();
+
+		let mut moves = vec![Move::new((0, 0), Some(Direction::Top), Signal::Default)];
+		for _ in 0..2 {
+			moves = world.tick(&mut state, moves, |_, _, _| {});
+			assert!(moves.iter().all(|mov| mov.signal() == &Signal::Bus(1 << 3)));
+		}
+		moves = world.tick(&mut state, moves, |_, _, _| {});
+		assert!(moves.contains(&Move::new((2, 1), Some(Direction::Top), Signal::Default)));
+
+		// a line going straight into a bus is dropped
+		let moves = world.tick(
+			&mut state,
+			vec![Move::new((1, 0), Some(Direction::Left), Signal::Default)],
+			|_, _, _| {},
+		);
+		assert_eq!(moves.len(), 0);
+	}
+
+	#[test]
+	fn receivers_only_hear_their_channel() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Transmitter(1);
+		*world.mut_at(10, 0) = Block::Receiver(1);
+		*world.mut_at(20, 0) = Block::Receiver(2);
+		let mut state = BlockStates::default();
+
+		let moves = world.tick(
+			&mut state,
+			vec![Move::new((0, 0), Some(Direction::Left), Signal::Default)],
+			|_, _, _| {},
+		);
+		assert_eq!(
+			moves,
+			vec![Move::Channel {
+				channel: 1,
+				signal: Signal::Default
+			}]
+		);
+		let moves = world.tick(&mut state, moves, |_, _, _| {});
+		assert_eq!(moves.len(), 4);
+		assert!(moves.iter().all(|mov| match mov {
+			Move::Inside { to, .. } => (9..=11).contains(&to.0),
+			_ => false,
+		}));
+	}
+
+	#[test]
+	fn blocks_placed_after_ticking_tick() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Wire(Direction::Right);
+		let mut state = BlockStates::default();
+
+		assert_eq!(world.tick(&mut state, vec![], |_, _, _| {}).len(), 0);
+		*world.mut_at(5, 5) = Block::Not(false);
+		assert_eq!(world.tick(&mut state, vec![], |_, _, _| {}).len(), 4);
+	}
+
+	#[test]
+	fn pin_info_follows_renumbered_pins() {
+		let mut world = World::default();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(1, 0) = Block::Input(2);
+		let info = PinInfo {
+			name: "carry in".to_string(),
+			..Default::default()
+		};
+		world.pins_mut().set(PinKind::Input, 2, info.clone());
+
+		world.io_blocks_fix();
+
+		assert_eq!(world.at(1, 0), Some(&Block::Input(1)));
+		assert_eq!(world.pins().get(PinKind::Input, 1), Some(&info));
+		assert_eq!(world.pins().get(PinKind::Input, 2), None);
+	}
+
+	#[test]
+	fn split_ticks_match_single_threaded_ones() {
+		// rows of wires with nots and delays in them, spanning a bunch of chunks
+		let mut world = World::default();
+		for y in -20..20 {
+			*world.mut_at(-40, y) = Block::Switch(y % 3 == 0);
+			for x in -39..40 {
+				*world.mut_at(x, y) = match (x + y).rem_euclid(7) {
+					0 => Block::Not(false),
+					3 => Block::Delay(Direction::Right, 2, 0),
+					_ => Block::Wire(Direction::Right),
+				};
+			}
+		}
+
+		let run = |split_from: usize| {
+			let mut state = BlockStates::default();
+			let mut moves = vec![];
+			let mut ticks = vec![];
+			for _ in 0..30 {
+				let mut drawn = vec![];
+				moves = world.tick_split_from(
+					&mut state,
+					moves,
+					|x, y, dt| drawn.push((x, y, dt)),
+					split_from,
+				);
+				ticks.push((moves.clone(), drawn));
+			}
+			(ticks, state)
+		};
+
+		let (single, single_state) = run(usize::MAX);
+		let (split, split_state) = run(0);
+		assert!(single.iter().any(|(moves, _)| moves.len() > 100));
+		assert_eq!(single, split);
+		assert_eq!(single_state, split_state);
+	}
+
+	#[test]
+	fn lookups_follow_changed_blocks() {
+		let mut world = World::default();
+		let wid = WorldId::default();
+		*world.mut_at(0, 0) = Block::Input(0);
+		*world.mut_at(-20, 3) = Block::Foreign(wid, 1, 0);
+		assert_eq!(world.find_input(0), Some((0, 0)));
+		assert_eq!(world.find_foreign(1, 0), Some((-20, 3)));
+
+		// the index is built now, these only get reindexed
+		*world.mut_at(0, 0) = Block::Nothing;
+		*world.mut_at(5, 5) = Block::Input(0);
+		world.map_at(-20, 3, |_| Block::Foreign(wid, 2, 0));
+		*world.mut_at(7, 7) = Block::Output(1);
+		*world.mut_at(8, 7) = Block::Output(1);
+
+		assert_eq!(world.find_input(0), Some((5, 5)));
+		assert_eq!(world.find_foreign(1, 0), None);
+		assert_eq!(world.find_foreign(2, 0), Some((-20, 3)));
+		assert_eq!(world.inputs_count(), 1);
+		assert_eq!(world.outputs_count(), 2);
+
+		// the duplicate output gets the next id, the lower coords keep theirs
+		world.io_blocks_fix();
+		assert_eq!(world.at(7, 7), Some(&Block::Output(0)));
+		assert_eq!(world.at(8, 7), Some(&Block::Output(1)));
+		assert_eq!(world.find_input(0), Some((5, 5)));
+	}
+}